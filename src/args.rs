@@ -1,19 +1,108 @@
-use chrono::NaiveDate;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
 
 #[derive(Debug, Copy, Clone)]
 pub struct TotalInstitutionInvestorsArgs {
     pub date: NaiveDate,
     pub date_type: DateType,
+    pub market: Market,
 }
 
 #[derive(Debug, Copy, Clone)]
 pub struct SingleStockInstitutionalInvestorsArgs {
     pub date: NaiveDate,
     pub date_type: DateType,
+    pub market: Market,
     pub industry_type: IndustryType,
 }
 
-#[allow(dead_code)]
+// TPEx has industries TWSE does not, e.g. 文化創意業
+#[derive(Debug, Copy, Clone)]
+pub struct TpexSingleStockInstitutionalInvestorsArgs {
+    pub date: NaiveDate,
+    pub date_type: DateType,
+    pub industry_type: TpexIndustryType,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct InstitutionalInvestorsRankingArgs {
+    pub date: NaiveDate,
+    pub date_type: DateType,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct DayTradingArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ShortSaleBalancesArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SecuritiesLendingArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct BlockTradeArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct AfterHoursTradingArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MarketDailySummaryArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct IntradayMarketStatisticsArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TaiexHistoryArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SectorIndicesArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ForeignHoldingsArgs {
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ExRightsArgs {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct CorporateEventsArgs {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct AnnouncementArgs {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TaifexOpenInterestArgs {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum DateType {
     Day,
@@ -21,7 +110,131 @@ pub enum DateType {
     Month,
 }
 
-#[allow(dead_code)]
+impl DateType {
+    pub fn value(&self) -> &str {
+        match *self {
+            DateType::Day => "day",
+            DateType::Week => "week",
+            DateType::Month => "month",
+        }
+    }
+
+    // the t query of tpex.org.tw
+    pub fn tpex_value(&self) -> &str {
+        match *self {
+            DateType::Day => "D",
+            DateType::Week => "W",
+            DateType::Month => "M",
+        }
+    }
+
+    // the dates to ask the reports of from..to by, days skip the weekends unless a single
+    // day is asked, e.g. a saturday made up for a holiday, weeks are dated on their monday
+    // and months on their first day, so a report always has the same date
+    pub fn report_dates(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let mut date = match *self {
+            DateType::Day if from == to => return vec![from],
+            DateType::Day => from,
+            DateType::Week => from - Duration::days(from.weekday().num_days_from_monday() as i64),
+            DateType::Month => from.with_day(1).unwrap_or(from),
+        };
+
+        let mut ret = vec![];
+        while date <= to {
+            let next = match *self {
+                DateType::Day => {
+                    if date.weekday() != Weekday::Sat && date.weekday() != Weekday::Sun {
+                        ret.push(date);
+                    }
+                    date.succ_opt()
+                }
+                DateType::Week => {
+                    ret.push(date);
+                    date.checked_add_signed(Duration::days(7))
+                }
+                DateType::Month => {
+                    ret.push(date);
+                    match date.month() {
+                        12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+                        month => NaiveDate::from_ymd_opt(date.year(), month + 1, 1),
+                    }
+                }
+            };
+            match next {
+                Some(next) => date = next,
+                None => break,
+            }
+        }
+
+        ret
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Language {
+    Chinese,
+    English,
+    // fetches both and keeps the English names next to the Chinese ones
+    Bilingual,
+}
+
+impl Language {
+    // the lang query, Bilingual queries in Chinese first
+    pub fn value(&self) -> &str {
+        match *self {
+            Language::Chinese | Language::Bilingual => "zh",
+            Language::English => "en",
+        }
+    }
+
+    // the l query of tpex.org.tw
+    pub fn tpex_value(&self) -> &str {
+        match *self {
+            Language::Chinese | Language::Bilingual => "zh-tw",
+            Language::English => "en-us",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Format {
+    Json,
+    // what the download buttons serve, sometimes still works when json is throttled
+    Csv,
+}
+
+impl Format {
+    pub fn value(&self) -> &str {
+        match *self {
+            Format::Json => "json",
+            Format::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RequestOptions {
+    pub language: Language,
+    pub format: Format,
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        RequestOptions {
+            language: Language::Chinese,
+            format: Format::Json,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Market {
+    // 上市
+    Twse,
+    // 上櫃
+    Tpex,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum IndustryType {
     All,
@@ -68,7 +281,6 @@ pub enum IndustryType {
     ConvertibleCorporateBonds,
 }
 
-#[allow(dead_code)]
 impl IndustryType {
     pub fn value(&self) -> &str {
         match *self {
@@ -116,4 +328,484 @@ impl IndustryType {
             IndustryType::ConvertibleCorporateBonds => "CB",
         }
     }
+
+    // the reverse of value, e.g. 24 for Semiconductor
+    pub fn from_value(value: &str) -> Option<IndustryType> {
+        match value {
+            "ALL" => Some(IndustryType::All),
+            "ALLBUT0999" => Some(IndustryType::AllWithoutWarrantsAndCBBCs),
+            "0049" => Some(IndustryType::ClosedEndFunds),
+            "ETF" => Some(IndustryType::ETF),
+            "ETN" => Some(IndustryType::ETN),
+            "019919T" => Some(IndustryType::BeneficialSecurities),
+            "0999GA" => Some(IndustryType::SpecialStockWithOptions),
+            "0999GD" => Some(IndustryType::CorporateBondsWithStockOptions),
+            "0999G9" => Some(IndustryType::Warrants),
+            "01" => Some(IndustryType::Cement),
+            "02" => Some(IndustryType::Food),
+            "03" => Some(IndustryType::Plastic),
+            "04" => Some(IndustryType::TextileFiber),
+            "05" => Some(IndustryType::ElectricalMachinery),
+            "06" => Some(IndustryType::ElectricalCable),
+            "07" => Some(IndustryType::MedicalTechnology),
+            "21" => Some(IndustryType::Chemical),
+            "22" => Some(IndustryType::BiotechMedical),
+            "08" => Some(IndustryType::GlassCeramics),
+            "09" => Some(IndustryType::Paper),
+            "10" => Some(IndustryType::Metal),
+            "11" => Some(IndustryType::Rubber),
+            "12" => Some(IndustryType::Car),
+            "13" => Some(IndustryType::Electronics),
+            "24" => Some(IndustryType::Semiconductor),
+            "25" => Some(IndustryType::ComputerAndPeripheralEquipment),
+            "26" => Some(IndustryType::Optoelectronics),
+            "27" => Some(IndustryType::CommunicationNetwork),
+            "28" => Some(IndustryType::ElectronicComponents),
+            "29" => Some(IndustryType::ElectronicChannel),
+            "30" => Some(IndustryType::InformationService),
+            "31" => Some(IndustryType::OtherElectronics),
+            "14" => Some(IndustryType::BuildingMaterialsConstruction),
+            "15" => Some(IndustryType::Shipping),
+            "16" => Some(IndustryType::Tourism),
+            "17" => Some(IndustryType::FinancialInsurance),
+            "18" => Some(IndustryType::TradeDepartmentStore),
+            "23" => Some(IndustryType::OilAndElectricityAndGas),
+            "9299" => Some(IndustryType::DepositoryReceipts),
+            "19" => Some(IndustryType::Comprehensive),
+            "20" => Some(IndustryType::Other),
+            "CB" => Some(IndustryType::ConvertibleCorporateBonds),
+            _ => None,
+        }
+    }
+
+    // maps the 產業別 used by the ISIN directory, e.g. 半導體業
+    pub fn from_name(name: &str) -> Option<IndustryType> {
+        match name {
+            "水泥工業" => Some(IndustryType::Cement),
+            "食品工業" => Some(IndustryType::Food),
+            "塑膠工業" => Some(IndustryType::Plastic),
+            "紡織纖維" => Some(IndustryType::TextileFiber),
+            "電機機械" => Some(IndustryType::ElectricalMachinery),
+            "電器電纜" => Some(IndustryType::ElectricalCable),
+            "化學工業" => Some(IndustryType::Chemical),
+            "生技醫療業" => Some(IndustryType::BiotechMedical),
+            "玻璃陶瓷" => Some(IndustryType::GlassCeramics),
+            "造紙工業" => Some(IndustryType::Paper),
+            "鋼鐵工業" => Some(IndustryType::Metal),
+            "橡膠工業" => Some(IndustryType::Rubber),
+            "汽車工業" => Some(IndustryType::Car),
+            "電子工業" => Some(IndustryType::Electronics),
+            "半導體業" => Some(IndustryType::Semiconductor),
+            "電腦及週邊設備業" => Some(IndustryType::ComputerAndPeripheralEquipment),
+            "光電業" => Some(IndustryType::Optoelectronics),
+            "通信網路業" => Some(IndustryType::CommunicationNetwork),
+            "電子零組件業" => Some(IndustryType::ElectronicComponents),
+            "電子通路業" => Some(IndustryType::ElectronicChannel),
+            "資訊服務業" => Some(IndustryType::InformationService),
+            "其他電子業" => Some(IndustryType::OtherElectronics),
+            "建材營造業" => Some(IndustryType::BuildingMaterialsConstruction),
+            "航運業" => Some(IndustryType::Shipping),
+            "觀光事業" => Some(IndustryType::Tourism),
+            "金融保險業" => Some(IndustryType::FinancialInsurance),
+            "貿易百貨業" => Some(IndustryType::TradeDepartmentStore),
+            "油電燃氣業" => Some(IndustryType::OilAndElectricityAndGas),
+            "存託憑證" => Some(IndustryType::DepositoryReceipts),
+            "綜合" => Some(IndustryType::Comprehensive),
+            "其他業" => Some(IndustryType::Other),
+            _ => None,
+        }
+    }
+}
+
+// the se query of tpex.org.tw, most of the codes are shared with IndustryType
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TpexIndustryType {
+    All,
+    AllWithoutWarrants,
+    Food,
+    Plastic,
+    TextileFiber,
+    ElectricalMachinery,
+    ElectricalCable,
+    Chemical,
+    BiotechMedical,
+    GlassCeramics,
+    Metal,
+    Rubber,
+    Semiconductor,
+    ComputerAndPeripheralEquipment,
+    Optoelectronics,
+    CommunicationNetwork,
+    ElectronicComponents,
+    ElectronicChannel,
+    InformationService,
+    OtherElectronics,
+    BuildingMaterialsConstruction,
+    Shipping,
+    Tourism,
+    FinancialInsurance,
+    TradeDepartmentStore,
+    OilAndElectricityAndGas,
+    CulturalCreative,
+    AgriculturalTechnology,
+    ECommerce,
+    Other,
+    ManagedStocks,
+}
+
+impl TpexIndustryType {
+    pub fn value(&self) -> &str {
+        match *self {
+            TpexIndustryType::All => "AL",
+            TpexIndustryType::AllWithoutWarrants => "EW",
+            TpexIndustryType::Food => "02",
+            TpexIndustryType::Plastic => "03",
+            TpexIndustryType::TextileFiber => "04",
+            TpexIndustryType::ElectricalMachinery => "05",
+            TpexIndustryType::ElectricalCable => "06",
+            TpexIndustryType::Chemical => "21",
+            TpexIndustryType::BiotechMedical => "22",
+            TpexIndustryType::GlassCeramics => "08",
+            TpexIndustryType::Metal => "10",
+            TpexIndustryType::Rubber => "11",
+            TpexIndustryType::Semiconductor => "24",
+            TpexIndustryType::ComputerAndPeripheralEquipment => "25",
+            TpexIndustryType::Optoelectronics => "26",
+            TpexIndustryType::CommunicationNetwork => "27",
+            TpexIndustryType::ElectronicComponents => "28",
+            TpexIndustryType::ElectronicChannel => "29",
+            TpexIndustryType::InformationService => "30",
+            TpexIndustryType::OtherElectronics => "31",
+            TpexIndustryType::BuildingMaterialsConstruction => "14",
+            TpexIndustryType::Shipping => "15",
+            TpexIndustryType::Tourism => "16",
+            TpexIndustryType::FinancialInsurance => "17",
+            TpexIndustryType::TradeDepartmentStore => "18",
+            TpexIndustryType::OilAndElectricityAndGas => "23",
+            TpexIndustryType::CulturalCreative => "32",
+            TpexIndustryType::AgriculturalTechnology => "33",
+            TpexIndustryType::ECommerce => "34",
+            TpexIndustryType::Other => "20",
+            TpexIndustryType::ManagedStocks => "80",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<TpexIndustryType> {
+        match value {
+            "AL" => Some(TpexIndustryType::All),
+            "EW" => Some(TpexIndustryType::AllWithoutWarrants),
+            "02" => Some(TpexIndustryType::Food),
+            "03" => Some(TpexIndustryType::Plastic),
+            "04" => Some(TpexIndustryType::TextileFiber),
+            "05" => Some(TpexIndustryType::ElectricalMachinery),
+            "06" => Some(TpexIndustryType::ElectricalCable),
+            "21" => Some(TpexIndustryType::Chemical),
+            "22" => Some(TpexIndustryType::BiotechMedical),
+            "08" => Some(TpexIndustryType::GlassCeramics),
+            "10" => Some(TpexIndustryType::Metal),
+            "11" => Some(TpexIndustryType::Rubber),
+            "24" => Some(TpexIndustryType::Semiconductor),
+            "25" => Some(TpexIndustryType::ComputerAndPeripheralEquipment),
+            "26" => Some(TpexIndustryType::Optoelectronics),
+            "27" => Some(TpexIndustryType::CommunicationNetwork),
+            "28" => Some(TpexIndustryType::ElectronicComponents),
+            "29" => Some(TpexIndustryType::ElectronicChannel),
+            "30" => Some(TpexIndustryType::InformationService),
+            "31" => Some(TpexIndustryType::OtherElectronics),
+            "14" => Some(TpexIndustryType::BuildingMaterialsConstruction),
+            "15" => Some(TpexIndustryType::Shipping),
+            "16" => Some(TpexIndustryType::Tourism),
+            "17" => Some(TpexIndustryType::FinancialInsurance),
+            "18" => Some(TpexIndustryType::TradeDepartmentStore),
+            "23" => Some(TpexIndustryType::OilAndElectricityAndGas),
+            "32" => Some(TpexIndustryType::CulturalCreative),
+            "33" => Some(TpexIndustryType::AgriculturalTechnology),
+            "34" => Some(TpexIndustryType::ECommerce),
+            "20" => Some(TpexIndustryType::Other),
+            "80" => Some(TpexIndustryType::ManagedStocks),
+            _ => None,
+        }
+    }
+
+    // None when the industry has no stock traded on TPEx, e.g. Cement
+    pub fn from_industry_type(industry_type: IndustryType) -> Option<TpexIndustryType> {
+        match industry_type {
+            IndustryType::All => Some(TpexIndustryType::All),
+            IndustryType::AllWithoutWarrantsAndCBBCs => Some(TpexIndustryType::AllWithoutWarrants),
+            IndustryType::Food => Some(TpexIndustryType::Food),
+            IndustryType::Plastic => Some(TpexIndustryType::Plastic),
+            IndustryType::TextileFiber => Some(TpexIndustryType::TextileFiber),
+            IndustryType::ElectricalMachinery => Some(TpexIndustryType::ElectricalMachinery),
+            IndustryType::ElectricalCable => Some(TpexIndustryType::ElectricalCable),
+            IndustryType::Chemical => Some(TpexIndustryType::Chemical),
+            IndustryType::BiotechMedical => Some(TpexIndustryType::BiotechMedical),
+            IndustryType::GlassCeramics => Some(TpexIndustryType::GlassCeramics),
+            IndustryType::Metal => Some(TpexIndustryType::Metal),
+            IndustryType::Rubber => Some(TpexIndustryType::Rubber),
+            IndustryType::Semiconductor => Some(TpexIndustryType::Semiconductor),
+            IndustryType::ComputerAndPeripheralEquipment => {
+                Some(TpexIndustryType::ComputerAndPeripheralEquipment)
+            }
+            IndustryType::Optoelectronics => Some(TpexIndustryType::Optoelectronics),
+            IndustryType::CommunicationNetwork => Some(TpexIndustryType::CommunicationNetwork),
+            IndustryType::ElectronicComponents => Some(TpexIndustryType::ElectronicComponents),
+            IndustryType::ElectronicChannel => Some(TpexIndustryType::ElectronicChannel),
+            IndustryType::InformationService => Some(TpexIndustryType::InformationService),
+            IndustryType::OtherElectronics => Some(TpexIndustryType::OtherElectronics),
+            IndustryType::BuildingMaterialsConstruction => {
+                Some(TpexIndustryType::BuildingMaterialsConstruction)
+            }
+            IndustryType::Shipping => Some(TpexIndustryType::Shipping),
+            IndustryType::Tourism => Some(TpexIndustryType::Tourism),
+            IndustryType::FinancialInsurance => Some(TpexIndustryType::FinancialInsurance),
+            IndustryType::TradeDepartmentStore => Some(TpexIndustryType::TradeDepartmentStore),
+            IndustryType::OilAndElectricityAndGas => {
+                Some(TpexIndustryType::OilAndElectricityAndGas)
+            }
+            IndustryType::Other => Some(TpexIndustryType::Other),
+            _ => None,
+        }
+    }
+
+    // maps the 產業別 used by the ISIN directory, including the TPEx only industries
+    pub fn from_name(name: &str) -> Option<TpexIndustryType> {
+        match name {
+            "文化創意業" => Some(TpexIndustryType::CulturalCreative),
+            "農業科技業" => Some(TpexIndustryType::AgriculturalTechnology),
+            "電子商務" => Some(TpexIndustryType::ECommerce),
+            "管理股票" => Some(TpexIndustryType::ManagedStocks),
+            _ => IndustryType::from_name(name).and_then(TpexIndustryType::from_industry_type),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Index {
+    Taiex,
+    Cement,
+    Food,
+    Plastic,
+    TextileFiber,
+    ElectricalMachinery,
+    ElectricalCable,
+    ChemicalBiotechMedical,
+    Chemical,
+    BiotechMedical,
+    GlassCeramics,
+    Paper,
+    Metal,
+    Rubber,
+    Car,
+    Electronics,
+    Semiconductor,
+    ComputerAndPeripheralEquipment,
+    Optoelectronics,
+    CommunicationNetwork,
+    ElectronicComponents,
+    ElectronicChannel,
+    InformationService,
+    OtherElectronics,
+    BuildingMaterialsConstruction,
+    Shipping,
+    Tourism,
+    FinancialInsurance,
+    TradeDepartmentStore,
+    OilAndElectricityAndGas,
+    Other,
+}
+
+impl Index {
+    pub const ALL: [Index; 31] = [
+        Index::Taiex,
+        Index::Cement,
+        Index::Food,
+        Index::Plastic,
+        Index::TextileFiber,
+        Index::ElectricalMachinery,
+        Index::ElectricalCable,
+        Index::ChemicalBiotechMedical,
+        Index::Chemical,
+        Index::BiotechMedical,
+        Index::GlassCeramics,
+        Index::Paper,
+        Index::Metal,
+        Index::Rubber,
+        Index::Car,
+        Index::Electronics,
+        Index::Semiconductor,
+        Index::ComputerAndPeripheralEquipment,
+        Index::Optoelectronics,
+        Index::CommunicationNetwork,
+        Index::ElectronicComponents,
+        Index::ElectronicChannel,
+        Index::InformationService,
+        Index::OtherElectronics,
+        Index::BuildingMaterialsConstruction,
+        Index::Shipping,
+        Index::Tourism,
+        Index::FinancialInsurance,
+        Index::TradeDepartmentStore,
+        Index::OilAndElectricityAndGas,
+        Index::Other,
+    ];
+
+    pub fn value(&self) -> &str {
+        match *self {
+            Index::Taiex => "發行量加權股價指數",
+            Index::Cement => "水泥類指數",
+            Index::Food => "食品類指數",
+            Index::Plastic => "塑膠類指數",
+            Index::TextileFiber => "紡織纖維類指數",
+            Index::ElectricalMachinery => "電機機械類指數",
+            Index::ElectricalCable => "電器電纜類指數",
+            Index::ChemicalBiotechMedical => "化學生技醫療類指數",
+            Index::Chemical => "化學類指數",
+            Index::BiotechMedical => "生技醫療類指數",
+            Index::GlassCeramics => "玻璃陶瓷類指數",
+            Index::Paper => "造紙類指數",
+            Index::Metal => "鋼鐵類指數",
+            Index::Rubber => "橡膠類指數",
+            Index::Car => "汽車類指數",
+            Index::Electronics => "電子類指數",
+            Index::Semiconductor => "半導體類指數",
+            Index::ComputerAndPeripheralEquipment => "電腦及週邊設備類指數",
+            Index::Optoelectronics => "光電類指數",
+            Index::CommunicationNetwork => "通信網路類指數",
+            Index::ElectronicComponents => "電子零組件類指數",
+            Index::ElectronicChannel => "電子通路類指數",
+            Index::InformationService => "資訊服務類指數",
+            Index::OtherElectronics => "其他電子類指數",
+            Index::BuildingMaterialsConstruction => "建材營造類指數",
+            Index::Shipping => "航運類指數",
+            Index::Tourism => "觀光類指數",
+            Index::FinancialInsurance => "金融保險類指數",
+            Index::TradeDepartmentStore => "貿易百貨類指數",
+            Index::OilAndElectricityAndGas => "油電燃氣類指數",
+            Index::Other => "其他類指數",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Index> {
+        Index::ALL
+            .iter()
+            .copied()
+            .find(|index| index.value() == name)
+    }
+
+    pub fn industry_type(&self) -> Option<IndustryType> {
+        match *self {
+            Index::Cement => Some(IndustryType::Cement),
+            Index::Food => Some(IndustryType::Food),
+            Index::Plastic => Some(IndustryType::Plastic),
+            Index::TextileFiber => Some(IndustryType::TextileFiber),
+            Index::ElectricalMachinery => Some(IndustryType::ElectricalMachinery),
+            Index::ElectricalCable => Some(IndustryType::ElectricalCable),
+            Index::Chemical => Some(IndustryType::Chemical),
+            Index::BiotechMedical => Some(IndustryType::BiotechMedical),
+            Index::GlassCeramics => Some(IndustryType::GlassCeramics),
+            Index::Paper => Some(IndustryType::Paper),
+            Index::Metal => Some(IndustryType::Metal),
+            Index::Rubber => Some(IndustryType::Rubber),
+            Index::Car => Some(IndustryType::Car),
+            Index::Electronics => Some(IndustryType::Electronics),
+            Index::Semiconductor => Some(IndustryType::Semiconductor),
+            Index::ComputerAndPeripheralEquipment => {
+                Some(IndustryType::ComputerAndPeripheralEquipment)
+            }
+            Index::Optoelectronics => Some(IndustryType::Optoelectronics),
+            Index::CommunicationNetwork => Some(IndustryType::CommunicationNetwork),
+            Index::ElectronicComponents => Some(IndustryType::ElectronicComponents),
+            Index::ElectronicChannel => Some(IndustryType::ElectronicChannel),
+            Index::InformationService => Some(IndustryType::InformationService),
+            Index::OtherElectronics => Some(IndustryType::OtherElectronics),
+            Index::BuildingMaterialsConstruction => {
+                Some(IndustryType::BuildingMaterialsConstruction)
+            }
+            Index::Shipping => Some(IndustryType::Shipping),
+            Index::Tourism => Some(IndustryType::Tourism),
+            Index::FinancialInsurance => Some(IndustryType::FinancialInsurance),
+            Index::TradeDepartmentStore => Some(IndustryType::TradeDepartmentStore),
+            Index::OilAndElectricityAndGas => Some(IndustryType::OilAndElectricityAndGas),
+            Index::Other => Some(IndustryType::Other),
+            Index::Taiex | Index::ChemicalBiotechMedical => None,
+        }
+    }
+
+    pub fn from_industry_type(industry_type: IndustryType) -> Option<Index> {
+        Index::ALL
+            .iter()
+            .copied()
+            .find(|index| match index.industry_type() {
+                Some(t) => t.value() == industry_type.value(),
+                None => false,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_report_dates() {
+        let test_cases = [
+            (
+                "2021-02-04",
+                "2021-02-09",
+                DateType::Day,
+                vec!["2021-02-04", "2021-02-05", "2021-02-08", "2021-02-09"],
+                "days skip the weekend",
+            ),
+            (
+                "2021-02-20",
+                "2021-02-20",
+                DateType::Day,
+                vec!["2021-02-20"],
+                "a single day is always asked",
+            ),
+            (
+                "2021-02-05",
+                "2021-02-15",
+                DateType::Week,
+                vec!["2021-02-01", "2021-02-08", "2021-02-15"],
+                "weeks are dated on monday",
+            ),
+            (
+                "2021-02-05",
+                "2021-02-05",
+                DateType::Week,
+                vec!["2021-02-01"],
+                "a single week is dated on monday too",
+            ),
+            (
+                "2020-11-15",
+                "2021-01-01",
+                DateType::Month,
+                vec!["2020-11-01", "2020-12-01", "2021-01-01"],
+                "months are dated on the first day and cross the year",
+            ),
+            ("2021-02-06", "2021-02-07", DateType::Day, vec![], "weekend"),
+            (
+                "2021-02-05",
+                "2021-02-01",
+                DateType::Day,
+                vec![],
+                "wrong range",
+            ),
+        ];
+
+        for (from, to, date_type, wants, description) in test_cases.iter() {
+            let wants: Vec<NaiveDate> = wants.iter().map(|d| date(d)).collect();
+            assert_eq!(
+                date_type.report_dates(date(from), date(to)),
+                wants,
+                "[{}]",
+                description
+            );
+        }
+    }
 }
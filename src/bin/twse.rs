@@ -0,0 +1,471 @@
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::process;
+use twse_rs::args::{
+    DateType, IndustryType, Language, Market, SingleStockInstitutionalInvestorsArgs,
+    TotalInstitutionInvestorsArgs, TpexIndustryType, TpexSingleStockInstitutionalInvestorsArgs,
+};
+use twse_rs::client::Client;
+use twse_rs::errors::TwseError;
+use twse_rs::writer::{CsvWriter, Headers, NdjsonWriter};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const EXIT_ERROR: i32 = 1;
+// clap exits with 2 on wrong arguments, so do the checks it cannot do on its own
+const EXIT_USAGE: i32 = 2;
+const EXIT_NO_DATA: i32 = 3;
+
+#[derive(Parser)]
+#[command(
+    name = "twse",
+    version,
+    about = "Fetches datasets from twse.com.tw",
+    after_help = "Exit codes: 0 when rows are written, 1 on errors, 2 on wrong arguments, 3 when there is no data"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 三大法人買賣超日報/週報/月報, the institutional investors of every stock
+    Institutional {
+        #[command(flatten)]
+        query: Query,
+        /// Industry code, e.g. 24 for semiconductors, or ALL
+        #[arg(long, default_value = "ALL", value_parser = parse_industry)]
+        industry: IndustryArg,
+    },
+    /// 三大法人買賣金額統計表, the institutional investor totals
+    Total {
+        #[command(flatten)]
+        query: Query,
+    },
+}
+
+#[derive(Args)]
+struct Query {
+    /// Report date, or the first one of a range, e.g. 2021-02-05
+    #[arg(long, value_parser = parse_date)]
+    date: NaiveDate,
+    /// Last report date of a range
+    #[arg(long, value_parser = parse_date)]
+    to: Option<NaiveDate>,
+    #[arg(long, value_enum, default_value_t = Period::Day)]
+    period: Period,
+    #[arg(long, value_enum, default_value_t = MarketArg::Twse)]
+    market: MarketArg,
+    #[arg(long, value_enum, default_value_t = LanguageArg::Zh)]
+    language: LanguageArg,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
+enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    fn date_type(self) -> DateType {
+        match self {
+            Period::Day => DateType::Day,
+            Period::Week => DateType::Week,
+            Period::Month => DateType::Month,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+enum MarketArg {
+    Twse,
+    Tpex,
+}
+
+impl MarketArg {
+    fn market(self) -> Market {
+        match self {
+            MarketArg::Twse => Market::Twse,
+            MarketArg::Tpex => Market::Tpex,
+        }
+    }
+}
+
+// TPEx has codes and industries TWSE does not, e.g. 32 for 文化創意業
+#[derive(Debug, Clone)]
+struct IndustryArg {
+    name: String,
+    twse: Option<IndustryType>,
+    tpex: Option<TpexIndustryType>,
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+enum LanguageArg {
+    Zh,
+    En,
+    // Chinese names with an extra column of English names
+    Both,
+}
+
+impl LanguageArg {
+    fn language(self) -> Language {
+        match self {
+            LanguageArg::Zh => Language::Chinese,
+            LanguageArg::En => Language::English,
+            LanguageArg::Both => Language::Bilingual,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+    Ndjson,
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, DATE_FORMAT).map_err(|e| format!("{}, e.g. 2021-02-05", e))
+}
+
+// accepts the code used by TWSE or TPEx or the 產業別 name, e.g. 24 or 半導體業
+fn parse_industry(s: &str) -> Result<IndustryArg, String> {
+    let twse = IndustryType::from_value(&s.to_uppercase()).or_else(|| IndustryType::from_name(s));
+    let tpex = TpexIndustryType::from_value(&s.to_uppercase())
+        .or_else(|| TpexIndustryType::from_name(s))
+        .or_else(|| twse.and_then(TpexIndustryType::from_industry_type));
+    if twse.is_none() && tpex.is_none() {
+        return Err(format!("unknown industry {}", s));
+    }
+
+    Ok(IndustryArg {
+        name: s.to_string(),
+        twse,
+        tpex,
+    })
+}
+
+// wrong arguments which clap cannot check, e.g. an industry of the other market
+#[derive(Debug)]
+struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let code = match run(cli) {
+        Ok(0) => {
+            eprintln!("no data");
+            EXIT_NO_DATA
+        }
+        Ok(_) => 0,
+        Err(e) if e.is::<UsageError>() => {
+            eprintln!("error: {}", e);
+            EXIT_USAGE
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            EXIT_ERROR
+        }
+    };
+    process::exit(code);
+}
+
+// returns the number of rows written
+fn run(cli: Cli) -> Result<usize, Box<dyn Error>> {
+    let (Command::Institutional { query, .. } | Command::Total { query }) = &cli.command;
+    if query.to.is_some_and(|to| to < query.date) {
+        return Err(Box::new(UsageError(
+            "--to is earlier than --date".to_string(),
+        )));
+    }
+
+    match cli.command {
+        Command::Institutional { query, industry } => {
+            let client = Client::default().language(query.language.language());
+            let reports = match query.market {
+                MarketArg::Twse => {
+                    let industry_type = industry.twse.ok_or_else(|| {
+                        UsageError(format!("industry {} is not traded on TWSE", industry.name))
+                    })?;
+                    fetch(&query, |date| {
+                        client.get_single_stock_institutional_investors(
+                            SingleStockInstitutionalInvestorsArgs {
+                                date,
+                                date_type: query.period.date_type(),
+                                market: Market::Twse,
+                                industry_type,
+                            },
+                        )
+                    })?
+                }
+                MarketArg::Tpex => {
+                    let industry_type = industry.tpex.ok_or_else(|| {
+                        UsageError(format!("industry {} is not traded on TPEx", industry.name))
+                    })?;
+                    fetch(&query, |date| {
+                        client.get_tpex_single_stock_institutional_investors(
+                            TpexSingleStockInstitutionalInvestorsArgs {
+                                date,
+                                date_type: query.period.date_type(),
+                                industry_type,
+                            },
+                        )
+                    })?
+                }
+            };
+            write(query.format, &reports)
+        }
+        Command::Total { query } => {
+            let client = Client::default().language(query.language.language());
+            let reports = fetch(&query, |date| {
+                client.get_total_institution_investors(TotalInstitutionInvestorsArgs {
+                    date,
+                    date_type: query.period.date_type(),
+                    market: query.market.market(),
+                })
+            })?;
+            write(query.format, &reports)
+        }
+    }
+}
+
+// fetches every report date of the query, a date without data is skipped
+fn fetch<T, F>(query: &Query, get: F) -> Result<Vec<(NaiveDate, Vec<T>)>, TwseError>
+where
+    F: Fn(NaiveDate) -> Result<Vec<T>, TwseError>,
+{
+    let mut ret = vec![];
+    let dates = query
+        .period
+        .date_type()
+        .report_dates(query.date, query.to.unwrap_or(query.date));
+    for date in dates {
+        match get(date) {
+            Ok(rows) => ret.push((date, rows)),
+            Err(e) if e.is_no_data() => {
+                eprintln!("{}: {}", date.format(DATE_FORMAT), e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(ret)
+}
+
+fn write<T: Serialize>(
+    format: OutputFormat,
+    reports: &[(NaiveDate, Vec<T>)],
+) -> Result<usize, Box<dyn Error>> {
+    let count = reports.iter().map(|(_, rows)| rows.len()).sum();
+    if count == 0 {
+        return Ok(0);
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match format {
+        OutputFormat::Table => write_table(&mut out, &records(reports)?)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &records(reports)?)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Csv => {
+            let mut writer = CsvWriter::new(&mut out, Headers::FieldNames);
+            for (date, rows) in reports.iter() {
+                writer.write(*date, rows)?;
+            }
+            writer.flush()?;
+        }
+        OutputFormat::Ndjson => {
+            let mut writer = NdjsonWriter::new(&mut out);
+            for (date, rows) in reports.iter() {
+                writer.write(*date, rows)?;
+            }
+            writer.flush()?;
+        }
+    }
+    out.flush()?;
+
+    Ok(count)
+}
+
+// every row gets the report date as its first column, some rows have a date of their own
+fn records<T: Serialize>(
+    reports: &[(NaiveDate, Vec<T>)],
+) -> Result<Vec<Map<String, Value>>, serde_json::Error> {
+    let mut ret = vec![];
+    for (date, rows) in reports.iter() {
+        for row in rows.iter() {
+            let mut record = Map::new();
+            record.insert(
+                "report_date".to_string(),
+                Value::String(date.format(DATE_FORMAT).to_string()),
+            );
+            if let Value::Object(fields) = serde_json::to_value(row)? {
+                record.extend(fields);
+            }
+            ret.push(record);
+        }
+    }
+
+    Ok(ret)
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+fn write_table<W: Write>(mut out: W, records: &[Map<String, Value>]) -> io::Result<()> {
+    let headers: Vec<String> = records[0].keys().cloned().collect();
+    let rows: Vec<Vec<(String, bool)>> = records
+        .iter()
+        .map(|record| {
+            record
+                .values()
+                .map(|value| (cell(value), value.is_number()))
+                .collect()
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| display_width(h)).collect();
+    for row in rows.iter() {
+        for (n, (cell, _)) in row.iter().enumerate() {
+            widths[n] = widths[n].max(display_width(cell));
+        }
+    }
+
+    let line: Vec<String> = headers
+        .iter()
+        .enumerate()
+        .map(|(n, header)| pad(header, widths[n], false))
+        .collect();
+    writeln!(out, "{}", line.join("  ").trim_end())?;
+    let line: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    writeln!(out, "{}", line.join("  "))?;
+    for row in rows.iter() {
+        let line: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(n, (cell, number))| pad(cell, widths[n], *number))
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())?;
+    }
+
+    Ok(())
+}
+
+// numbers are aligned to the right
+fn pad(s: &str, width: usize, right: bool) -> String {
+    let padding = " ".repeat(width - display_width(s));
+    if right {
+        format!("{}{}", padding, s)
+    } else {
+        format!("{}{}", s, padding)
+    }
+}
+
+// Chinese characters take two columns in a terminal
+fn display_width(s: &str) -> usize {
+    s.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6 => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_industry() {
+        let test_cases = [
+            ("24", Some("24"), Some("24"), "shared code"),
+            ("all", Some("ALL"), Some("AL"), "all of both markets"),
+            ("半導體業", Some("24"), Some("24"), "shared name"),
+            ("01", Some("01"), None, "twse only"),
+            ("文化創意業", None, Some("32"), "tpex only name"),
+            ("80", None, Some("80"), "tpex only code"),
+        ];
+
+        for (input, twse, tpex, description) in test_cases.iter() {
+            let got = parse_industry(input).unwrap();
+            assert_eq!(
+                got.twse.map(|t| t.value().to_string()).as_deref(),
+                *twse,
+                "[{}]",
+                description
+            );
+            assert_eq!(
+                got.tpex.map(|t| t.value().to_string()).as_deref(),
+                *tpex,
+                "[{}]",
+                description
+            );
+        }
+        assert!(parse_industry("99").is_err());
+    }
+
+    #[test]
+    fn test_run_with_wrong_range() {
+        let cli = Cli::try_parse_from([
+            "twse",
+            "total",
+            "--date",
+            "2021-02-05",
+            "--to",
+            "2021-02-01",
+        ])
+        .unwrap();
+        assert!(run(cli).unwrap_err().is::<UsageError>());
+    }
+
+    #[test]
+    fn test_write_table() {
+        let mut record = Map::new();
+        record.insert("stock_name".to_string(), Value::from("台泥"));
+        record.insert("buy".to_string(), Value::from(1_316_473u64));
+        let mut other = Map::new();
+        other.insert("stock_name".to_string(), Value::from("TCC"));
+        other.insert("buy".to_string(), Value::from(5u64));
+
+        let mut out = vec![];
+        write_table(&mut out, &[record, other]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "stock_name  buy\n----------  -------\n台泥        1316473\nTCC               5\n"
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use crate::errors::TwseError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+
+// a numeric table cell, TWSE writes markers instead of numbers where there is no value
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Cell<T> {
+    Value(T),
+    // --, ---, N/A and X, or 除權/除息 in the change columns on ex-rights days
+    NotApplicable,
+    // 暫停交易, 停止交易
+    Suspended,
+    // an empty cell
+    Missing,
+}
+
+impl<T> Cell<T> {
+    pub fn value(self) -> Option<T> {
+        match self {
+            Cell::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+// the markers are written as empty cells, or null in json
+impl<T: Serialize> Serialize for Cell<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Cell::Value(v) => v.serialize(serializer),
+            _ => serializer.serialize_none(),
+        }
+    }
+}
+
+pub trait CellValue: Sized {
+    fn from_cell(s: &str) -> Option<Self>;
+}
+
+impl CellValue for u64 {
+    fn from_cell(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+}
+
+impl CellValue for i64 {
+    fn from_cell(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+}
+
+impl CellValue for Decimal {
+    fn from_cell(s: &str) -> Option<Self> {
+        Decimal::from_str(s.strip_prefix('+').unwrap_or(s)).ok()
+    }
+}
+
+pub fn parse<T: CellValue>(s: &str) -> Result<Cell<T>, TwseError> {
+    let unexpected = || TwseError::CellError(s.to_string());
+    let text = strip_markup(s).ok_or_else(unexpected)?;
+
+    match text.as_str() {
+        "" => Ok(Cell::Missing),
+        "--" | "---" | "N/A" | "X" | "除權" | "除息" | "除權息" => Ok(Cell::NotApplicable),
+        "暫停交易" | "停止交易" => Ok(Cell::Suspended),
+        text => number(text)
+            .and_then(|number| T::from_cell(&number))
+            .map(Cell::Value)
+            .ok_or_else(unexpected),
+    }
+}
+
+// drops the thousands separators, which have to sit between groups of three digits,
+// e.g. -1,415.00 but not 1,41 or 1,,415
+fn number(text: &str) -> Option<String> {
+    let (sign, unsigned) = match text.strip_prefix(|c| c == '+' || c == '-') {
+        Some(unsigned) => (&text[..1], unsigned),
+        None => ("", text),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+
+    let mut groups = integer.split(',');
+    let first = groups.next()?;
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !digits(first) || !groups.all(|group| group.len() == 3 && digits(group)) {
+        return None;
+    }
+    if integer.contains(',') && first.len() > 3 {
+        return None;
+    }
+
+    let mut ret = format!("{}{}", sign, integer.replace(',', ""));
+    if let Some(fraction) = fraction {
+        if !digits(fraction) {
+            return None;
+        }
+        ret.push('.');
+        ret.push_str(fraction);
+    }
+    Some(ret)
+}
+
+// removes the tags of colored cells, e.g. <p style= color:red>+</p>1.50,
+// None when a tag is never closed
+fn strip_markup(s: &str) -> Option<String> {
+    let mut ret = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.replace("&nbsp;", " ").chars() {
+        match c {
+            '<' if in_tag => return None,
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => ret.push(c),
+            _ => {}
+        }
+    }
+    if in_tag {
+        return None;
+    }
+
+    Some(ret.trim().to_string())
+}
+
+pub(crate) fn parse_cell<'de, D, T>(deserializer: D) -> Result<Cell<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: CellValue,
+{
+    let s = String::deserialize(deserializer)?;
+    parse(&s).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse() {
+        let test_cases = [
+            ("1,316,473", Cell::Value(1316473), "thousands separators"),
+            ("-581,233", Cell::Value(-581233), "negative"),
+            ("+3,000", Cell::Value(3000), "plus sign"),
+            (
+                "<p style= color:red>+</p>12",
+                Cell::Value(12),
+                "colored sign",
+            ),
+            (" 0 ", Cell::Value(0), "padded"),
+            ("&nbsp;7", Cell::Value(7), "html space"),
+            ("--", Cell::NotApplicable, "no value"),
+            ("---", Cell::NotApplicable, "no value"),
+            ("N/A", Cell::NotApplicable, "not available"),
+            ("X", Cell::NotApplicable, "not comparable"),
+            ("除息", Cell::NotApplicable, "ex-dividend"),
+            (
+                "<p style= color:red>除權息</p>",
+                Cell::NotApplicable,
+                "colored",
+            ),
+            ("暫停交易", Cell::Suspended, "suspended"),
+            ("", Cell::Missing, "empty"),
+            ("<p style= color:red></p>", Cell::Missing, "empty tag"),
+        ];
+
+        for (input, want, description) in test_cases.iter() {
+            let got = parse::<i64>(input);
+            assert!(
+                matches!(&got, Ok(cell) if cell == want),
+                "[{}]: input:{} got:{:?}",
+                description,
+                input,
+                got
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(
+            parse::<Decimal>("1,415.00").unwrap(),
+            Cell::Value(dec!(1415.00))
+        );
+        assert_eq!(
+            parse::<Decimal>("<p style= color:green>-</p>1.95").unwrap(),
+            Cell::Value(dec!(-1.95))
+        );
+        assert_eq!(parse::<Decimal>("+0.50").unwrap(), Cell::Value(dec!(0.50)));
+    }
+
+    #[test]
+    fn test_parse_unexpected() {
+        let test_cases = [
+            "<p style= color:red>+</p>",
+            "-",
+            ".",
+            "1.2.3",
+            "1,,000",
+            ",100",
+            "100,",
+            "1,.5",
+            "1,41",
+            "1234,567",
+            "1.",
+            "1_000",
+            "<p style= color:red 12",
+            "12 34",
+            "abc",
+        ];
+
+        for input in test_cases.iter() {
+            assert!(
+                parse::<Decimal>(input).is_err(),
+                "{} should not be a decimal",
+                input
+            );
+            assert!(
+                parse::<i64>(input).is_err(),
+                "{} should not be an i64",
+                input
+            );
+        }
+        assert!(parse::<u64>("-1").is_err());
+    }
+
+    fn with_separators(digits: &str) -> String {
+        let mut ret = String::new();
+        for (n, c) in digits.chars().enumerate() {
+            if n > 0 && (digits.len() - n).is_multiple_of(3) {
+                ret.push(',');
+            }
+            ret.push(c);
+        }
+        ret
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(s in "\\PC*") {
+            let _ = parse::<u64>(&s);
+            let _ = parse::<i64>(&s);
+            let _ = parse::<Decimal>(&s);
+        }
+
+        #[test]
+        fn test_parse_markup_never_panics(s in "[<>/ pstyle=:colr+\\-,.0-9X除息-]*") {
+            let _ = parse::<i64>(&s);
+            let _ = parse::<Decimal>(&s);
+        }
+
+        #[test]
+        fn test_parse_u64(v in any::<u64>(), colored in any::<bool>()) {
+            let mut s = with_separators(&v.to_string());
+            if colored {
+                s = format!("<p style= color:red>{}</p>", s);
+            }
+            prop_assert_eq!(parse::<u64>(&s).unwrap(), Cell::Value(v));
+        }
+
+        #[test]
+        fn test_parse_i64(v in any::<i64>(), plus in any::<bool>()) {
+            let digits = with_separators(&v.unsigned_abs().to_string());
+            let s = match (v < 0, plus) {
+                (true, _) => format!("<p style= color:green>-</p>{}", digits),
+                (false, true) => format!("+{}", digits),
+                (false, false) => digits,
+            };
+            prop_assert_eq!(parse::<i64>(&s).unwrap(), Cell::Value(v));
+        }
+
+        #[test]
+        fn test_parse_decimal_roundtrip(mantissa in -999_999_999_999i64..999_999_999_999, scale in 0u32..5) {
+            let want = Decimal::new(mantissa, scale);
+            let s = want.to_string();
+            let (integer, fraction) = match s.trim_start_matches('-').split_once('.') {
+                Some((integer, fraction)) => (integer.to_string(), format!(".{}", fraction)),
+                None => (s.trim_start_matches('-').to_string(), String::new()),
+            };
+            let sign = if want.is_sign_negative() { "-" } else { "" };
+            let s = format!("{}{}{}", sign, with_separators(&integer), fraction);
+            prop_assert_eq!(parse::<Decimal>(&s).unwrap(), Cell::Value(want));
+        }
+    }
+}
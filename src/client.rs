@@ -0,0 +1,573 @@
+use crate::args::{
+    AfterHoursTradingArgs, AnnouncementArgs, BlockTradeArgs, DateType, DayTradingArgs,
+    ExRightsArgs, ForeignHoldingsArgs, Format, Index, IndustryType,
+    InstitutionalInvestorsRankingArgs, IntradayMarketStatisticsArgs, Language, Market,
+    MarketDailySummaryArgs, RequestOptions, SectorIndicesArgs, SecuritiesLendingArgs,
+    ShortSaleBalancesArgs, SingleStockInstitutionalInvestorsArgs, TaiexHistoryArgs,
+    TotalInstitutionInvestorsArgs, TpexSingleStockInstitutionalInvestorsArgs,
+};
+use crate::crawler;
+use crate::errors::TwseError;
+use crate::openapi;
+use crate::schema::{
+    AttentionStock, BlockTrade, DailyQuote, DayTrading, DayTradingSummary, DealersRanking,
+    DispositionStock, ExRightsResult, ExRightsSchedule, FixedPriceTrading, ForeignHolding,
+    ForeignInvestorsRanking, IntradayMarketStatistics, InvestmentTrustRanking, MarketDailySummary,
+    OddLotTrading, SectorIndex, SecuritiesLendingTransaction, ShortSaleBalances,
+    SingleStockInstitutionalInvestors, TaiexHistory, TotalInstitutionalInvestors,
+};
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Backend {
+    // www.twse.com.tw
+    Website,
+    // openapi.twse.com.tw, falls back to the website where it has no endpoint, has no
+    // rows for the date or fails
+    OpenApi,
+}
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    backend: Backend,
+    language: Language,
+    format: Format,
+    openapi_link: String,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new(Backend::Website)
+    }
+}
+
+impl Client {
+    pub fn new(backend: Backend) -> Client {
+        Client {
+            backend,
+            language: Language::Chinese,
+            format: Format::Json,
+            openapi_link: openapi::OPENAPI_LINK.to_string(),
+        }
+    }
+
+    // points the OpenAPI backend to another host, e.g. a local stand-in server
+    pub fn openapi_link(mut self, link: &str) -> Client {
+        self.openapi_link = link.to_string();
+        self
+    }
+
+    // the language of titles, fields and names, only the website backend has English
+    pub fn language(mut self, language: Language) -> Client {
+        self.language = language;
+        self
+    }
+
+    // the response format of the website backend, clone the client to pick one per request,
+    // only the institutional investors reports of TWSE have csv, the other endpoints fail
+    // with a FormatError rather than fetching json instead
+    pub fn format(mut self, format: Format) -> Client {
+        self.format = format;
+        self
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    pub fn get_single_stock_institutional_investors(
+        &self,
+        args: SingleStockInstitutionalInvestorsArgs,
+    ) -> Result<Vec<SingleStockInstitutionalInvestors>, TwseError> {
+        // the OpenAPI only has the whole TWSE market of a day in Chinese
+        if matches!(args.date_type, DateType::Day)
+            && args.market == Market::Twse
+            && matches!(args.industry_type, IndustryType::All)
+            && self.language == Language::Chinese
+            && self.openapi_serves(args.date)
+        {
+            let rows = openapi::get_single_stock_institutional_investors(&self.openapi_link);
+            if let Some(rows) = openapi_rows(rows, |_| true) {
+                return Ok(rows);
+            }
+        }
+
+        match self.language {
+            Language::Bilingual => {
+                let rows = crawler::get_single_stock_institutional_investors_with_options(
+                    args,
+                    self.options(Language::Chinese),
+                )?;
+                let english = crawler::get_single_stock_institutional_investors_with_options(
+                    args,
+                    self.options(Language::English),
+                )?;
+                Ok(SingleStockInstitutionalInvestors::with_english_names(
+                    rows, english,
+                ))
+            }
+            language => crawler::get_single_stock_institutional_investors_with_options(
+                args,
+                self.options(language),
+            ),
+        }
+    }
+
+    pub fn get_tpex_single_stock_institutional_investors(
+        &self,
+        args: TpexSingleStockInstitutionalInvestorsArgs,
+    ) -> Result<Vec<SingleStockInstitutionalInvestors>, TwseError> {
+        self.json_only()?;
+
+        match self.language {
+            Language::Bilingual => {
+                let rows = crawler::get_tpex_single_stock_institutional_investors(
+                    args,
+                    Language::Chinese,
+                )?;
+                let english = crawler::get_tpex_single_stock_institutional_investors(
+                    args,
+                    Language::English,
+                )?;
+                Ok(SingleStockInstitutionalInvestors::with_english_names(
+                    rows, english,
+                ))
+            }
+            language => crawler::get_tpex_single_stock_institutional_investors(args, language),
+        }
+    }
+
+    pub fn get_total_institution_investors(
+        &self,
+        args: TotalInstitutionInvestorsArgs,
+    ) -> Result<Vec<TotalInstitutionalInvestors>, TwseError> {
+        if matches!(args.date_type, DateType::Day)
+            && args.market == Market::Twse
+            && self.language == Language::Chinese
+            && self.openapi_serves(args.date)
+        {
+            let rows = openapi::get_total_institution_investors(&self.openapi_link);
+            if let Some(rows) = openapi_rows(rows, |_| true) {
+                return Ok(rows);
+            }
+        }
+
+        match self.language {
+            Language::Bilingual => {
+                let rows = crawler::get_total_institution_investors_with_options(
+                    args,
+                    self.options(Language::Chinese),
+                )?;
+                let english = crawler::get_total_institution_investors_with_options(
+                    args,
+                    self.options(Language::English),
+                )?;
+                Ok(TotalInstitutionalInvestors::with_english_names(
+                    rows, english,
+                ))
+            }
+            language => {
+                crawler::get_total_institution_investors_with_options(args, self.options(language))
+            }
+        }
+    }
+
+    fn json_only(&self) -> Result<(), TwseError> {
+        match self.format {
+            Format::Json => Ok(()),
+            format => Err(TwseError::FormatError(format.value().to_string())),
+        }
+    }
+
+    fn options(&self, language: Language) -> RequestOptions {
+        RequestOptions {
+            language,
+            format: self.format,
+        }
+    }
+
+    pub fn get_market_daily_summaries(
+        &self,
+        args: MarketDailySummaryArgs,
+    ) -> Result<Vec<MarketDailySummary>, TwseError> {
+        self.json_only()?;
+
+        if self.backend == Backend::OpenApi {
+            let rows = openapi::get_market_daily_summaries(&self.openapi_link);
+            if let Some(rows) = openapi_rows(rows, |row| same_month(row.date(), args.date)) {
+                return Ok(rows);
+            }
+        }
+
+        crawler::get_market_daily_summaries(args)
+    }
+
+    pub fn get_taiex_history(
+        &self,
+        args: TaiexHistoryArgs,
+    ) -> Result<Vec<TaiexHistory>, TwseError> {
+        self.json_only()?;
+
+        if self.backend == Backend::OpenApi {
+            let rows = openapi::get_taiex_history(&self.openapi_link);
+            if let Some(rows) = openapi_rows(rows, |row| same_month(row.date(), args.date)) {
+                return Ok(rows);
+            }
+        }
+
+        crawler::get_taiex_history(args)
+    }
+
+    // 每日收盤行情 of the latest trading day
+    pub fn get_daily_quotes(&self) -> Result<Vec<DailyQuote>, TwseError> {
+        self.json_only()?;
+
+        if self.backend == Backend::OpenApi {
+            let rows = openapi::get_daily_quotes(&self.openapi_link);
+            if let Some(rows) = openapi_rows(rows, |_| true) {
+                return Ok(rows);
+            }
+        }
+
+        crawler::get_daily_quotes()
+    }
+
+    pub fn get_foreign_holdings(
+        &self,
+        args: ForeignHoldingsArgs,
+    ) -> Result<Vec<ForeignHolding>, TwseError> {
+        self.json_only()?;
+
+        if self.openapi_serves(args.date) {
+            let rows = openapi::get_foreign_holdings(&self.openapi_link);
+            if let Some(rows) = openapi_rows(rows, |_| true) {
+                return Ok(rows);
+            }
+        }
+
+        crawler::get_foreign_holdings(args)
+    }
+
+    // the daily datasets of the OpenAPI carry no date, they are all of the latest
+    // trading day which FMTQIK tells
+    fn openapi_serves(&self, date: NaiveDate) -> bool {
+        self.backend == Backend::OpenApi
+            && openapi::get_market_daily_summaries(&self.openapi_link)
+                .ok()
+                .and_then(|rows| rows.iter().map(|row| row.date()).max())
+                == Some(date)
+    }
+
+    pub fn get_foreign_investors_ranking(
+        &self,
+        args: InstitutionalInvestorsRankingArgs,
+    ) -> Result<Vec<ForeignInvestorsRanking>, TwseError> {
+        self.json_only()?;
+        crawler::get_foreign_investors_ranking(args)
+    }
+
+    pub fn get_investment_trust_ranking(
+        &self,
+        args: InstitutionalInvestorsRankingArgs,
+    ) -> Result<Vec<InvestmentTrustRanking>, TwseError> {
+        self.json_only()?;
+        crawler::get_investment_trust_ranking(args)
+    }
+
+    pub fn get_dealers_ranking(
+        &self,
+        args: InstitutionalInvestorsRankingArgs,
+    ) -> Result<Vec<DealersRanking>, TwseError> {
+        self.json_only()?;
+        crawler::get_dealers_ranking(args)
+    }
+
+    pub fn get_day_trading(&self, args: DayTradingArgs) -> Result<Vec<DayTrading>, TwseError> {
+        self.json_only()?;
+        crawler::get_day_trading(args)
+    }
+
+    pub fn get_day_trading_summary(
+        &self,
+        args: DayTradingArgs,
+    ) -> Result<DayTradingSummary, TwseError> {
+        self.json_only()?;
+        crawler::get_day_trading_summary(args)
+    }
+
+    pub fn get_short_sale_balances(
+        &self,
+        args: ShortSaleBalancesArgs,
+    ) -> Result<Vec<ShortSaleBalances>, TwseError> {
+        self.json_only()?;
+        crawler::get_short_sale_balances(args)
+    }
+
+    pub fn get_securities_lending_transactions(
+        &self,
+        args: SecuritiesLendingArgs,
+    ) -> Result<Vec<SecuritiesLendingTransaction>, TwseError> {
+        self.json_only()?;
+        crawler::get_securities_lending_transactions(args)
+    }
+
+    pub fn get_block_trades(&self, args: BlockTradeArgs) -> Result<Vec<BlockTrade>, TwseError> {
+        self.json_only()?;
+        crawler::get_block_trades(args)
+    }
+
+    pub fn get_fixed_price_trading(
+        &self,
+        args: AfterHoursTradingArgs,
+    ) -> Result<Vec<FixedPriceTrading>, TwseError> {
+        self.json_only()?;
+        crawler::get_fixed_price_trading(args)
+    }
+
+    pub fn get_odd_lot_trading(
+        &self,
+        args: AfterHoursTradingArgs,
+    ) -> Result<Vec<OddLotTrading>, TwseError> {
+        self.json_only()?;
+        crawler::get_odd_lot_trading(args)
+    }
+
+    pub fn get_intraday_market_statistics(
+        &self,
+        args: IntradayMarketStatisticsArgs,
+    ) -> Result<Vec<IntradayMarketStatistics>, TwseError> {
+        self.json_only()?;
+        crawler::get_intraday_market_statistics(args)
+    }
+
+    pub fn get_sector_indices(
+        &self,
+        args: SectorIndicesArgs,
+    ) -> Result<HashMap<Index, SectorIndex>, TwseError> {
+        self.json_only()?;
+        crawler::get_sector_indices(args)
+    }
+
+    pub fn get_ex_rights_schedule(
+        &self,
+        args: ExRightsArgs,
+    ) -> Result<Vec<ExRightsSchedule>, TwseError> {
+        self.json_only()?;
+        crawler::get_ex_rights_schedule(args)
+    }
+
+    pub fn get_ex_rights_results(
+        &self,
+        args: ExRightsArgs,
+    ) -> Result<Vec<ExRightsResult>, TwseError> {
+        self.json_only()?;
+        crawler::get_ex_rights_results(args)
+    }
+
+    pub fn get_attention_stocks(
+        &self,
+        args: AnnouncementArgs,
+    ) -> Result<Vec<AttentionStock>, TwseError> {
+        self.json_only()?;
+        crawler::get_attention_stocks(args)
+    }
+
+    pub fn get_disposition_stocks(
+        &self,
+        args: AnnouncementArgs,
+    ) -> Result<Vec<DispositionStock>, TwseError> {
+        self.json_only()?;
+        crawler::get_disposition_stocks(args)
+    }
+}
+
+// the OpenAPI rows when one of them is for the date asked, None goes to the website,
+// which is also where a failing OpenAPI goes
+fn openapi_rows<T, F: Fn(&T) -> bool>(
+    rows: Result<Vec<T>, TwseError>,
+    for_date: F,
+) -> Option<Vec<T>> {
+    rows.ok().filter(|rows| rows.iter().any(for_date))
+}
+
+// the OpenAPI only serves the current month, older months go to the website
+fn same_month(a: NaiveDate, b: NaiveDate) -> bool {
+    a.year() == b.year() && a.month() == b.month()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::serve;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_same_month() {
+        let date = NaiveDate::from_ymd_opt(2021, 2, 5).unwrap();
+        assert!(same_month(
+            date,
+            NaiveDate::from_ymd_opt(2021, 2, 1).unwrap()
+        ));
+        assert!(!same_month(
+            date,
+            NaiveDate::from_ymd_opt(2021, 3, 5).unwrap()
+        ));
+        assert!(!same_month(
+            date,
+            NaiveDate::from_ymd_opt(2020, 2, 5).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_openapi_rows() {
+        let rows: Result<Vec<u32>, TwseError> = Ok(vec![1, 2]);
+        assert_eq!(openapi_rows(rows, |row| *row == 2), Some(vec![1, 2]));
+        let rows: Result<Vec<u32>, TwseError> = Ok(vec![1, 2]);
+        assert_eq!(openapi_rows(rows, |row| *row == 3), None);
+        let rows: Result<Vec<u32>, TwseError> = Err(TwseError::TWSEError("OK".to_string()));
+        assert_eq!(openapi_rows(rows, |_| true), None);
+    }
+
+    #[test]
+    fn test_openapi_backend() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let link = serve(move |path| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let body = match path {
+                "/exchangeReport/FMTQIK" => {
+                    r#"[{"Date":"1100204","TradeVolume":"6,901,113,044","TradeValue":"283,071,812,914","Transaction":"1,618,032","TAIEX":"15,760.05","Change":"349.96"},{"Date":"1100205","TradeVolume":"7,251,385,462","TradeValue":"305,624,553,487","Transaction":"1,752,329","TAIEX":"15,802.39","Change":"42.34"}]"#
+                }
+                "/indicesReport/MI_5MINS_HIST" => {
+                    r#"[{"Date":"1100201","OpeningIndex":"15,221.29","HighestIndex":"15,443.92","LowestIndex":"15,221.29","ClosingIndex":"15,410.09"}]"#
+                }
+                "/fund/BFI82U" => {
+                    r#"[{"Item":"外資及陸資(不含外資自營商)","TotalBuy":"125,542,154,521","TotalSell":"114,826,447,133","Difference":"10,715,707,388"}]"#
+                }
+                "/fund/MI_QFIIS" => {
+                    r#"[{"Code":"2330","Name":"台積電","ISINCode":"TW0002330008","IssuedShares":"25,930,380,458","AvailableShares":"11,503,329,116","SharesHeld":"14,427,051,342","AvailableInvestPer":"44.36","SharesHeldPer":"55.63","Upperlimit":"100.00","ChineseUpperlimit":"100.00"}]"#
+                }
+                _ => return None,
+            };
+            Some(body.to_string())
+        });
+        let client = Client::new(Backend::OpenApi).openapi_link(&link);
+        assert_eq!(client.backend(), Backend::OpenApi);
+        let date = NaiveDate::from_ymd_opt(2021, 2, 5).unwrap();
+
+        let got = client.get_taiex_history(TaiexHistoryArgs { date }).unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let got = client
+            .get_total_institution_investors(TotalInstitutionInvestorsArgs {
+                date,
+                date_type: DateType::Day,
+                market: Market::Twse,
+            })
+            .unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        let got = client
+            .get_foreign_holdings(ForeignHoldingsArgs { date })
+            .unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].stock_id(), "2330");
+        assert_eq!(requests.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_csv_only_for_institutional_investors() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let link = serve(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            None
+        });
+        let client = Client::new(Backend::OpenApi)
+            .openapi_link(&link)
+            .format(Format::Csv);
+        let date = NaiveDate::from_ymd_opt(2021, 2, 5).unwrap();
+
+        // fails before anything is fetched instead of answering with json
+        let got = client.get_taiex_history(TaiexHistoryArgs { date });
+        assert!(matches!(got, Err(TwseError::FormatError(f)) if f == "csv"));
+        let got = client.get_foreign_holdings(ForeignHoldingsArgs { date });
+        assert!(matches!(got, Err(TwseError::FormatError(_))));
+        let got = client.get_block_trades(BlockTradeArgs { date });
+        assert!(matches!(got, Err(TwseError::FormatError(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_openapi_fallback() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        // the stand-in has nothing, every request goes on to the website
+        let link = serve(|_| None);
+        let client = Client::new(Backend::OpenApi).openapi_link(&link);
+
+        let got = client.get_taiex_history(TaiexHistoryArgs { date });
+        assert!(got.is_ok(), "taiex history");
+        let got = client.get_foreign_holdings(ForeignHoldingsArgs { date });
+        assert!(got.is_ok(), "foreign holdings");
+    }
+
+    #[test]
+    fn test_languages() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+
+        for language in [Language::Chinese, Language::English, Language::Bilingual] {
+            let client = Client::default().language(language);
+            let got = client.get_total_institution_investors(TotalInstitutionInvestorsArgs {
+                date,
+                date_type: DateType::Day,
+                market: Market::Twse,
+            });
+            assert!(got.is_ok(), "[{:?}] total institution investors", language);
+            let got = client.get_single_stock_institutional_investors(
+                SingleStockInstitutionalInvestorsArgs {
+                    date,
+                    date_type: DateType::Day,
+                    market: Market::Twse,
+                    industry_type: IndustryType::Cement,
+                },
+            );
+            assert!(
+                got.is_ok(),
+                "[{:?}] single stock institutional investors",
+                language
+            );
+        }
+    }
+
+    #[test]
+    fn test_formats() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+
+        for format in [Format::Json, Format::Csv] {
+            let client = Client::default().format(format);
+            let got = client.get_total_institution_investors(TotalInstitutionInvestorsArgs {
+                date,
+                date_type: DateType::Day,
+                market: Market::Twse,
+            });
+            assert!(got.is_ok(), "[{:?}] total institution investors", format);
+        }
+    }
+
+    #[test]
+    fn test_backends() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+
+        for backend in [Backend::Website, Backend::OpenApi] {
+            let client = Client::new(backend);
+            let got = client.get_market_daily_summaries(MarketDailySummaryArgs { date });
+            assert!(got.is_ok(), "[{:?}] market daily summaries", backend);
+            let got = client.get_taiex_history(TaiexHistoryArgs { date });
+            assert!(got.is_ok(), "[{:?}] taiex history", backend);
+        }
+    }
+}
@@ -0,0 +1,535 @@
+use crate::args::{DateType, Market};
+use crate::cell::Cell;
+use crate::errors::TwseError;
+use arrow::array::{ArrayRef, Date32Array, Decimal128Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Date32Type, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use chrono::NaiveDate;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use rust_decimal::Decimal;
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// rust_decimal keeps at most 28 digits, 4 of them are enough for prices and ratios,
+// a value with more is refused instead of rounded
+const DECIMAL_PRECISION: u8 = 28;
+const DECIMAL_SCALE: i8 = 4;
+// the date of the report, the rows of some reports have their own date column
+const REPORT_DATE: &str = "report_date";
+
+// a row type written as one arrow column per field, see arrow_row!
+pub trait ArrowRow: Sized {
+    // the directory of the rows in the parquet files, e.g. total_institutional_investors
+    const DATASET: &'static str;
+
+    fn fields() -> Vec<Field>;
+    fn columns(rows: &[Self]) -> Result<Vec<ArrayRef>, TwseError>;
+}
+
+// a field type of the rows and the arrow column it is written as, None is written as null
+// for the nullable types wrapping another one, e.g. Cell
+pub trait ArrowValue: Sized {
+    fn data_type() -> DataType;
+    fn nullable() -> bool {
+        false
+    }
+    fn array<'a, I: Iterator<Item = Option<&'a Self>>>(values: I) -> Result<ArrayRef, TwseError>
+    where
+        Self: 'a;
+}
+
+impl ArrowValue for String {
+    fn data_type() -> DataType {
+        DataType::Utf8
+    }
+
+    fn array<'a, I: Iterator<Item = Option<&'a Self>>>(values: I) -> Result<ArrayRef, TwseError> {
+        Ok(Arc::new(values.collect::<StringArray>()))
+    }
+}
+
+impl ArrowValue for Option<String> {
+    fn data_type() -> DataType {
+        DataType::Utf8
+    }
+
+    fn nullable() -> bool {
+        true
+    }
+
+    fn array<'a, I: Iterator<Item = Option<&'a Self>>>(values: I) -> Result<ArrayRef, TwseError> {
+        Ok(Arc::new(
+            values
+                .map(|v| v.and_then(|v| v.as_deref()))
+                .collect::<StringArray>(),
+        ))
+    }
+}
+
+// written as signed like the differences, a count past i64::MAX is refused instead of wrapped
+impl ArrowValue for u64 {
+    fn data_type() -> DataType {
+        DataType::Int64
+    }
+
+    fn array<'a, I: Iterator<Item = Option<&'a Self>>>(values: I) -> Result<ArrayRef, TwseError> {
+        let values = values
+            .map(|v| {
+                v.map(|v| i64::try_from(*v).map_err(|_| TwseError::IntegerError(v.to_string())))
+                    .transpose()
+            })
+            .collect::<Result<Int64Array, TwseError>>()?;
+        Ok(Arc::new(values))
+    }
+}
+
+impl ArrowValue for i64 {
+    fn data_type() -> DataType {
+        DataType::Int64
+    }
+
+    fn array<'a, I: Iterator<Item = Option<&'a Self>>>(values: I) -> Result<ArrayRef, TwseError> {
+        Ok(Arc::new(values.map(|v| v.copied()).collect::<Int64Array>()))
+    }
+}
+
+impl ArrowValue for Decimal {
+    fn data_type() -> DataType {
+        DataType::Decimal128(DECIMAL_PRECISION, DECIMAL_SCALE)
+    }
+
+    fn array<'a, I: Iterator<Item = Option<&'a Self>>>(values: I) -> Result<ArrayRef, TwseError> {
+        let values = values
+            .map(|v| {
+                v.map(|v| {
+                    let mut scaled = *v;
+                    scaled.rescale(DECIMAL_SCALE as u32);
+                    if scaled.scale() != DECIMAL_SCALE as u32 || scaled != *v {
+                        return Err(TwseError::DecimalError(v.to_string()));
+                    }
+                    Ok(scaled.mantissa())
+                })
+                .transpose()
+            })
+            .collect::<Result<Decimal128Array, TwseError>>()?;
+        Ok(Arc::new(values.with_data_type(Self::data_type())))
+    }
+}
+
+impl ArrowValue for NaiveDate {
+    fn data_type() -> DataType {
+        DataType::Date32
+    }
+
+    fn array<'a, I: Iterator<Item = Option<&'a Self>>>(values: I) -> Result<ArrayRef, TwseError> {
+        Ok(Arc::new(
+            values
+                .map(|v| v.map(|v| Date32Type::from_naive_date(*v)))
+                .collect::<Date32Array>(),
+        ))
+    }
+}
+
+// the markers have no value and are written as null
+impl<T: ArrowValue> ArrowValue for Cell<T> {
+    fn data_type() -> DataType {
+        T::data_type()
+    }
+
+    fn nullable() -> bool {
+        true
+    }
+
+    fn array<'a, I: Iterator<Item = Option<&'a Self>>>(values: I) -> Result<ArrayRef, TwseError>
+    where
+        Self: 'a,
+    {
+        T::array(values.map(|v| match v {
+            Some(Cell::Value(v)) => Some(v),
+            _ => None,
+        }))
+    }
+}
+
+// the type of the column comes from the field the getter points at
+pub(crate) fn field<R, T: ArrowValue>(name: &str, _: fn(&R) -> &T) -> Field {
+    Field::new(name, T::data_type(), T::nullable())
+}
+
+pub(crate) fn column<R, T: ArrowValue>(
+    rows: &[R],
+    get: fn(&R) -> &T,
+) -> Result<ArrayRef, TwseError> {
+    T::array(rows.iter().map(get).map(Some))
+}
+
+// implements ArrowRow in the module of the row, as the fields are private
+macro_rules! arrow_row {
+    ($row:ty, $dataset:expr, [$($field:ident),* $(,)?]) => {
+        impl crate::columnar::ArrowRow for $row {
+            const DATASET: &'static str = $dataset;
+
+            fn fields() -> Vec<arrow::datatypes::Field> {
+                vec![$(crate::columnar::field(stringify!($field), |row: &$row| &row.$field)),*]
+            }
+
+            fn columns(
+                rows: &[Self],
+            ) -> Result<Vec<arrow::array::ArrayRef>, crate::errors::TwseError> {
+                Ok(vec![$(crate::columnar::column(rows, |row: &$row| &row.$field)?),*])
+            }
+        }
+    };
+}
+pub(crate) use arrow_row;
+
+pub fn schema<T: ArrowRow>() -> Schema {
+    let mut fields = vec![Field::new(REPORT_DATE, DataType::Date32, false)];
+    fields.extend(T::fields());
+    Schema::new(fields)
+}
+
+// the rows of a report with the report date as the first column
+pub fn record_batch<T: ArrowRow>(date: NaiveDate, rows: &[T]) -> Result<RecordBatch, TwseError> {
+    let dates = vec![date; rows.len()];
+    let mut columns = vec![NaiveDate::array(dates.iter().map(Some))?];
+    columns.extend(T::columns(rows)?);
+
+    Ok(RecordBatch::try_new(Arc::new(schema::<T>()), columns)?)
+}
+
+// the hive style partition of a report, e.g. date_type=day/market=twse/industry=24/date=2021-02-05,
+// the keys a dataset is not asked by are left out
+#[derive(Debug, Clone)]
+pub struct Partition {
+    date: NaiveDate,
+    keys: Vec<(&'static str, String)>,
+}
+
+impl Partition {
+    pub fn new(date: NaiveDate) -> Partition {
+        Partition { date, keys: vec![] }
+    }
+
+    pub fn date_type(mut self, date_type: DateType) -> Partition {
+        self.keys.push(("date_type", date_type.value().to_string()));
+        self
+    }
+
+    pub fn market(mut self, market: Market) -> Partition {
+        let market = match market {
+            Market::Twse => "twse",
+            Market::Tpex => "tpex",
+        };
+        self.keys.push(("market", market.to_string()));
+        self
+    }
+
+    // the code of IndustryType or TpexIndustryType, e.g. 24 or ALL
+    pub fn industry(mut self, industry: &str) -> Partition {
+        self.keys.push(("industry", industry.to_string()));
+        self
+    }
+
+    fn dir(&self) -> PathBuf {
+        let mut dir: PathBuf = self
+            .keys
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        dir.push(format!("date={}", self.date.format("%Y-%m-%d")));
+        dir
+    }
+}
+
+// writes a parquet file per report under <root>/<dataset>/<partition>/part-0.parquet,
+// a report fetched again replaces its file
+pub struct ParquetWriter {
+    root: PathBuf,
+}
+
+impl ParquetWriter {
+    pub fn new<P: Into<PathBuf>>(root: P) -> ParquetWriter {
+        ParquetWriter { root: root.into() }
+    }
+
+    pub fn path<T: ArrowRow>(&self, partition: &Partition) -> PathBuf {
+        self.root
+            .join(T::DATASET)
+            .join(partition.dir())
+            .join("part-0.parquet")
+    }
+
+    pub fn write<T: ArrowRow>(
+        &self,
+        partition: &Partition,
+        rows: &[T],
+    ) -> Result<PathBuf, TwseError> {
+        let batch = record_batch(partition.date, rows)?;
+        let path = self.path::<T>(partition);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // a half written file is never left at the path
+        let partial = path.with_extension("parquet.partial");
+        write_parquet(&partial, &batch)?;
+        fs::rename(&partial, &path)?;
+
+        Ok(path)
+    }
+}
+
+fn write_parquet(path: &Path, batch: &RecordBatch) -> Result<(), TwseError> {
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), Some(properties))?;
+    writer.write(batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{
+        Data, ReportResponse, Response, TaiexHistory, TotalInstitutionalInvestors,
+    };
+    use arrow::array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    fn rows<T>(response: &str, cast: fn(Data) -> Option<T>) -> Vec<T> {
+        let response: Response = serde_json::from_str(response).unwrap();
+        response.data.into_iter().filter_map(cast).collect()
+    }
+
+    fn total_rows() -> Vec<TotalInstitutionalInvestors> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 三大法人買賣金額統計表",
+            "fields":["單位名稱","買進金額","賣出金額","買賣差額"],
+            "data":[
+                ["自營商(自行買賣)","3,720,692,397","2,657,469,622","1,063,222,775"],
+                ["合計","87,532,849,369","89,714,793,209","-2,181,943,840"]
+            ]
+        }
+        "#;
+        rows(response, |data| match data {
+            Data::TotalInstitutionalInvestors(d) => Some(d),
+            _ => None,
+        })
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, 2, 5).unwrap()
+    }
+
+    #[test]
+    fn test_record_batch() {
+        let batch = record_batch(date(), &total_rows()).unwrap();
+        let wants = [
+            ("report_date", DataType::Date32, false),
+            ("name", DataType::Utf8, false),
+            ("buy", DataType::Int64, false),
+            ("sell", DataType::Int64, false),
+            ("difference", DataType::Int64, false),
+            ("name_en", DataType::Utf8, true),
+        ];
+        let schema = batch.schema();
+        assert_eq!(schema.fields().len(), wants.len());
+        for (field, (name, data_type, nullable)) in schema.fields().iter().zip(wants.iter()) {
+            assert_eq!(field.name(), name);
+            assert_eq!(field.data_type(), data_type, "[{}]", name);
+            assert_eq!(field.is_nullable(), *nullable, "[{}]", name);
+        }
+
+        assert_eq!(batch.num_rows(), 2);
+        let dates = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(dates.value_as_date(1), Some(date()));
+        let names = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(names.value(1), "合計");
+        let differences = batch
+            .column(4)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(differences.value(1), -2181943840);
+        assert!(batch.column(5).is_null(0));
+    }
+
+    #[test]
+    fn test_record_batch_with_decimals() {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210201",
+            "title":"110年02月 發行量加權股價指數歷史資料",
+            "fields":["日期","開盤指數","最高指數","最低指數","收盤指數"],
+            "data":[
+                ["110/02/01","15,410.09","15,651.58","15,291.51","15,410.09"],
+                ["110/02/02","15,597.94","15,769.40","15,537.58","15,760.05"]
+            ]
+        }
+        "#;
+        let rows = serde_json::from_str::<ReportResponse<TaiexHistory>>(response)
+            .unwrap()
+            .data;
+        assert_eq!(rows.len(), 2);
+
+        let batch = record_batch::<TaiexHistory>(date(), &rows).unwrap();
+        let dates = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(dates.value_as_date(0), NaiveDate::from_ymd_opt(2021, 2, 1));
+        let closes = batch
+            .column(5)
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        assert_eq!(closes.data_type(), &DataType::Decimal128(28, 4));
+        assert_eq!(closes.value_as_string(1), "15760.0500");
+    }
+
+    #[test]
+    fn test_record_batch_with_too_many_decimals() {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210201",
+            "title":"110年02月 發行量加權股價指數歷史資料",
+            "fields":["日期","開盤指數","最高指數","最低指數","收盤指數"],
+            "data":[
+                ["110/02/01","15,410.09","15,651.58","15,291.51","15,410.09125"]
+            ]
+        }
+        "#;
+        let rows = serde_json::from_str::<ReportResponse<TaiexHistory>>(response)
+            .unwrap()
+            .data;
+
+        let got = record_batch::<TaiexHistory>(date(), &rows);
+        assert!(matches!(got, Err(TwseError::DecimalError(_))));
+    }
+
+    struct Trade {
+        volume: u64,
+        price: Cell<Decimal>,
+    }
+
+    arrow_row!(Trade, "trades", [volume, price]);
+
+    #[test]
+    fn test_record_batch_with_cells() {
+        let rows = [
+            Trade {
+                volume: 341000,
+                price: Cell::Value(Decimal::new(632, 0)),
+            },
+            Trade {
+                volume: 0,
+                price: Cell::NotApplicable,
+            },
+        ];
+
+        let batch = record_batch(date(), &rows).unwrap();
+        let field = batch.schema().field(2).clone();
+        assert_eq!(field.data_type(), &DataType::Decimal128(28, 4));
+        assert!(field.is_nullable());
+        let prices = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        assert_eq!(prices.value_as_string(0), "632.0000");
+        assert!(prices.is_null(1));
+    }
+
+    #[test]
+    fn test_record_batch_with_too_large_integers() {
+        let rows = [Trade {
+            volume: u64::MAX,
+            price: Cell::Missing,
+        }];
+
+        let got = record_batch(date(), &rows);
+        assert!(matches!(got, Err(TwseError::IntegerError(_))));
+    }
+
+    #[test]
+    fn test_partition_dir() {
+        let test_cases = [
+            (Partition::new(date()), "date=2021-02-05", "date only"),
+            (
+                Partition::new(date())
+                    .date_type(DateType::Month)
+                    .market(Market::Tpex)
+                    .industry("32"),
+                "date_type=month/market=tpex/industry=32/date=2021-02-05",
+                "every key",
+            ),
+        ];
+
+        for (partition, want, description) in test_cases.iter() {
+            assert_eq!(partition.dir(), PathBuf::from(want), "[{}]", description);
+        }
+    }
+
+    #[test]
+    fn test_parquet_writer() {
+        let root = std::env::temp_dir().join(format!("twse-rs-parquet-{}", std::process::id()));
+        let writer = ParquetWriter::new(&root);
+
+        let partition = Partition::new(date())
+            .date_type(DateType::Day)
+            .market(Market::Twse);
+        let path = writer.write(&partition, &total_rows()).unwrap();
+        assert_eq!(
+            path,
+            root.join(
+                "total_institutional_investors/date_type=day/market=twse/date=2021-02-05/part-0.parquet"
+            )
+        );
+        // the weekly report of the same date is another file
+        let weekly = Partition::new(date())
+            .date_type(DateType::Week)
+            .market(Market::Twse);
+        assert_ne!(writer.write(&weekly, &total_rows()).unwrap(), path);
+        // fetched again
+        let path = writer.write(&partition, &total_rows()[1..]).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(
+            batches[0].schema().as_ref(),
+            &schema::<TotalInstitutionalInvestors>()
+        );
+        assert_eq!(
+            batches[0],
+            record_batch(date(), &total_rows()[1..]).unwrap()
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use crate::args::CorporateEventsArgs;
+use crate::crawler::{get_capital_reductions, get_par_value_changes, get_trading_suspensions};
+use crate::errors::TwseError;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CorporateEvent {
+    // 減資, effective from the day trading resumes
+    CapitalReduction {
+        stock_id: String,
+        stock_name: String,
+        effective_date: NaiveDate,
+        last_close: Decimal,
+        reference_price: Decimal,
+        reason: String,
+    },
+    // 暫停交易
+    Suspension {
+        stock_id: String,
+        stock_name: String,
+        effective_date: NaiveDate,
+        resume_date: Option<NaiveDate>,
+    },
+    // 恢復交易
+    Resumption {
+        stock_id: String,
+        stock_name: String,
+        effective_date: NaiveDate,
+    },
+    // 變更面額, effective from the day trading resumes
+    ParValueChange {
+        stock_id: String,
+        stock_name: String,
+        effective_date: NaiveDate,
+        last_close: Decimal,
+        reference_price: Decimal,
+    },
+}
+
+impl CorporateEvent {
+    pub fn stock_id(&self) -> &str {
+        match self {
+            CorporateEvent::CapitalReduction { stock_id, .. }
+            | CorporateEvent::Suspension { stock_id, .. }
+            | CorporateEvent::Resumption { stock_id, .. }
+            | CorporateEvent::ParValueChange { stock_id, .. } => stock_id,
+        }
+    }
+
+    pub fn effective_date(&self) -> NaiveDate {
+        match self {
+            CorporateEvent::CapitalReduction { effective_date, .. }
+            | CorporateEvent::Suspension { effective_date, .. }
+            | CorporateEvent::Resumption { effective_date, .. }
+            | CorporateEvent::ParValueChange { effective_date, .. } => *effective_date,
+        }
+    }
+}
+
+// TWSE filters by the announcement date, so events effective outside the range are dropped,
+// e.g. the resumption of a suspension in the range which resumes afterwards
+pub fn get_corporate_events(args: CorporateEventsArgs) -> Result<Vec<CorporateEvent>, TwseError> {
+    let mut events: Vec<CorporateEvent> = vec![];
+    for d in or_empty(get_capital_reductions(args))? {
+        events.push(d.into_event());
+    }
+    for d in or_empty(get_trading_suspensions(args))? {
+        events.extend(d.into_events());
+    }
+    for d in or_empty(get_par_value_changes(args))? {
+        events.push(d.into_event());
+    }
+
+    Ok(in_range(events, args.start_date, args.end_date))
+}
+
+// keeps the events effective within the dates, ordered by the date and then the stock
+fn in_range(
+    mut events: Vec<CorporateEvent>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Vec<CorporateEvent> {
+    events.retain(|e| e.effective_date() >= start_date && e.effective_date() <= end_date);
+    events.sort_by(|a, b| {
+        a.effective_date()
+            .cmp(&b.effective_date())
+            .then_with(|| a.stock_id().cmp(b.stock_id()))
+    });
+
+    events
+}
+
+// a source without announcements in the range does not fail the others
+fn or_empty<T>(rows: Result<Vec<T>, TwseError>) -> Result<Vec<T>, TwseError> {
+    match rows {
+        Err(e) if e.is_no_data() => Ok(vec![]),
+        rows => rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::NO_DATA;
+
+    #[test]
+    fn test_or_empty() {
+        let got = or_empty::<u64>(Err(TwseError::TWSEError(NO_DATA.to_string())));
+        assert_eq!(got.unwrap(), Vec::<u64>::new(), "[no data should be empty]");
+
+        let got = or_empty::<u64>(Err(TwseError::TWSEError("查詢日期大於今日".to_string())));
+        assert!(got.is_err(), "[other stats should fail]");
+
+        let got = or_empty(Ok(vec![1u64]));
+        assert_eq!(got.unwrap(), vec![1], "[rows should be kept]");
+    }
+
+    #[test]
+    fn test_in_range() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let resumption = |stock_id: &str, effective_date: &str| CorporateEvent::Resumption {
+            stock_id: stock_id.to_string(),
+            stock_name: String::new(),
+            effective_date: date(effective_date),
+        };
+        let events = vec![
+            resumption("2936", "2021-02-08"),
+            resumption("2888", "2021-01-18"),
+            resumption("1337", "2021-02-04"),
+            resumption("6531", "2021-01-04"),
+            resumption("1101", "2021-01-18"),
+            resumption("2330", "2021-01-03"),
+        ];
+
+        let got: Vec<(String, NaiveDate)> =
+            in_range(events, date("2021-01-04"), date("2021-02-05"))
+                .iter()
+                .map(|e| (e.stock_id().to_string(), e.effective_date()))
+                .collect();
+        let wants = vec![
+            ("6531".to_string(), date("2021-01-04")),
+            ("1101".to_string(), date("2021-01-18")),
+            ("2888".to_string(), date("2021-01-18")),
+            ("1337".to_string(), date("2021-02-04")),
+        ];
+        assert_eq!(
+            got, wants,
+            "[events outside the range should be dropped, the rest sorted]"
+        );
+    }
+
+    #[test]
+    fn test_get_corporate_events() {
+        let start_date = NaiveDate::parse_from_str("2021-01-04", "%Y-%m-%d").unwrap();
+        let end_date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                CorporateEventsArgs {
+                    start_date: start_date,
+                    end_date: end_date,
+                },
+                "date range should be ok",
+                true,
+            ),
+            (
+                CorporateEventsArgs {
+                    start_date: wrong_date,
+                    end_date: wrong_date,
+                },
+                "date range should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_corporate_events(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+}
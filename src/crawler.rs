@@ -1,46 +1,92 @@
 use crate::args::{
-    DateType, IndustryType, SingleStockInstitutionalInvestorsArgs, TotalInstitutionInvestorsArgs,
+    AfterHoursTradingArgs, AnnouncementArgs, BlockTradeArgs, CorporateEventsArgs, DateType,
+    DayTradingArgs, ExRightsArgs, ForeignHoldingsArgs, Format, Index,
+    InstitutionalInvestorsRankingArgs, IntradayMarketStatisticsArgs, Language, Market,
+    MarketDailySummaryArgs, RequestOptions, SectorIndicesArgs, SecuritiesLendingArgs,
+    ShortSaleBalancesArgs, SingleStockInstitutionalInvestorsArgs, TaiexHistoryArgs,
+    TotalInstitutionInvestorsArgs, TpexIndustryType, TpexSingleStockInstitutionalInvestorsArgs,
 };
-use crate::errors::TwseError;
+use crate::csv_response;
+use crate::encoding;
+use crate::errors::{TwseError, NO_DATA};
 use crate::schema::{
-    Data, Response, SingleStockInstitutionalInvestors, TotalInstitutionalInvestors,
+    format_roc_date, AttentionStock, BlockTrade, CapitalReduction, DailyQuote, DailyQuoteResponse,
+    Data, DayTrading, DayTradingResponse, DayTradingSummary, DealersRanking, DispositionStock,
+    ExRightsResult, ExRightsSchedule, FixedPriceTrading, ForeignHolding, ForeignHoldingResponse,
+    ForeignInvestorsRanking, Header, IntradayMarketStatistics, InvestmentTrustRanking,
+    MarketDailySummary, OddLotTrading, ParValueChange, ReportResponse, Response, SectorIndex,
+    SectorIndexResponse, SecuritiesLendingTransaction, ShortSaleBalances,
+    SingleStockInstitutionalInvestors, TaiexHistory, TotalInstitutionalInvestors, TpexData,
+    TpexResponse, TradingSuspension, SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS,
+    TOTAL_INSTITUTIONAL_INVESTORS_HEADERS,
 };
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
 
 const SINGLE_INVESTORS_DAY_LINK: &str = "https://www.twse.com.tw/fund/T86";
 const SINGLE_INVESTORS_WEEK_LINK: &str = "https://www.twse.com.tw/fund/TWT54U";
 const SINGLE_INVESTORS_MONTH_LINK: &str = "https://www.twse.com.tw/fund/TWT47U";
 const INVESTORS_LINK: &str = "https://www.twse.com.tw/fund/BFI82U";
+const FOREIGN_INVESTORS_RANKING_LINK: &str = "https://www.twse.com.tw/fund/TWT38U";
+const INVESTMENT_TRUST_RANKING_LINK: &str = "https://www.twse.com.tw/fund/TWT44U";
+const DEALERS_RANKING_LINK: &str = "https://www.twse.com.tw/fund/TWT43U";
+const DAY_TRADING_LINK: &str = "https://www.twse.com.tw/exchangeReport/TWTB4U";
+const SHORT_SALE_BALANCES_LINK: &str = "https://www.twse.com.tw/exchangeReport/TWT93U";
+const SECURITIES_LENDING_LINK: &str = "https://www.twse.com.tw/SBL/TWT72U";
+const BLOCK_TRADE_LINK: &str = "https://www.twse.com.tw/block/BFIAUU";
+const FIXED_PRICE_TRADING_LINK: &str = "https://www.twse.com.tw/exchangeReport/BFT41U";
+const ODD_LOT_TRADING_LINK: &str = "https://www.twse.com.tw/exchangeReport/TWT53U";
+const MARKET_DAILY_SUMMARY_LINK: &str = "https://www.twse.com.tw/exchangeReport/FMTQIK";
+const INTRADAY_MARKET_STATISTICS_LINK: &str = "https://www.twse.com.tw/exchangeReport/MI_5MINS";
+const TAIEX_HISTORY_LINK: &str = "https://www.twse.com.tw/indicesReport/MI_5MINS_HIST";
+const SECTOR_INDICES_LINK: &str = "https://www.twse.com.tw/exchangeReport/MI_INDEX";
+const DAILY_QUOTES_LINK: &str = "https://www.twse.com.tw/exchangeReport/STOCK_DAY_ALL";
+const FOREIGN_HOLDINGS_LINK: &str = "https://www.twse.com.tw/fund/MI_QFIIS";
+const EX_RIGHTS_SCHEDULE_LINK: &str = "https://www.twse.com.tw/exchangeReport/TWT48U";
+const EX_RIGHTS_RESULT_LINK: &str = "https://www.twse.com.tw/exchangeReport/TWT49U";
+const CAPITAL_REDUCTION_LINK: &str = "https://www.twse.com.tw/exchangeReport/TWTAUU";
+const TRADING_SUSPENSION_LINK: &str = "https://www.twse.com.tw/exchangeReport/TWTAWU";
+const PAR_VALUE_CHANGE_LINK: &str = "https://www.twse.com.tw/exchangeReport/TWTAVU";
+const ATTENTION_STOCKS_LINK: &str = "https://www.twse.com.tw/announcement/notice";
+const DISPOSITION_STOCKS_LINK: &str = "https://www.twse.com.tw/announcement/punish";
+const TPEX_SINGLE_INVESTORS_LINK: &str =
+    "https://www.tpex.org.tw/web/stock/3insti/daily_trade/3itrade_hedge_result.php";
+const TPEX_INVESTORS_LINK: &str =
+    "https://www.tpex.org.tw/web/stock/3insti/3insti_summary/3itrdsum_result.php";
 const TWSE_STATE_OK: &str = "OK";
 
-fn get_single_stock_institutional_investors(
+pub(crate) fn get_single_stock_institutional_investors_with_options(
     args: SingleStockInstitutionalInvestorsArgs,
+    options: RequestOptions,
 ) -> Result<Vec<SingleStockInstitutionalInvestors>, TwseError> {
-    let date = format!("{}", args.date.format("%Y%m%d"));
-    let mut request = ureq::get(SINGLE_INVESTORS_DAY_LINK)
-        .query("response", "json")
-        .query("date", &date);
-
-    match args.date_type {
-        DateType::Day => request = request.query("selectType", args.industry_type.value()),
-        DateType::Week => {
-            request = ureq::get(SINGLE_INVESTORS_WEEK_LINK)
-                .query("response", "json")
-                .query("date", &date)
-                .query("selectType", args.industry_type.value())
-        }
-
-        DateType::Month => {
-            request = ureq::get(SINGLE_INVESTORS_MONTH_LINK)
-                .query("response", "json")
-                .query("date", &date)
-                .query("selectType", args.industry_type.value())
-        }
+    if args.market == Market::Tpex {
+        let industry_type = TpexIndustryType::from_industry_type(args.industry_type)
+            .ok_or_else(|| TwseError::IndustryError(format!("{:?}", args.industry_type)))?;
+        let args = TpexSingleStockInstitutionalInvestorsArgs {
+            date: args.date,
+            date_type: args.date_type,
+            industry_type,
+        };
+        return get_tpex_single_stock_institutional_investors(args, options.language);
     }
 
-    let response: Response = request.call()?.into_json()?;
-    if response.stat != TWSE_STATE_OK {
-        return Err(TwseError::TWSEError(response.stat));
-    }
+    let link = match args.date_type {
+        DateType::Day => SINGLE_INVESTORS_DAY_LINK,
+        DateType::Week => SINGLE_INVESTORS_WEEK_LINK,
+        DateType::Month => SINGLE_INVESTORS_MONTH_LINK,
+    };
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(link)
+        .query("date", &date)
+        .query("selectType", args.industry_type.value());
+
+    let response = get_response(
+        request,
+        options,
+        args.date,
+        &SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS,
+    )?;
 
     let mut ret: Vec<SingleStockInstitutionalInvestors> = vec![];
     for data in response.data {
@@ -54,10 +100,15 @@ fn get_single_stock_institutional_investors(
     Ok(ret)
 }
 
-fn get_total_institution_investors(
+pub(crate) fn get_total_institution_investors_with_options(
     args: TotalInstitutionInvestorsArgs,
+    options: RequestOptions,
 ) -> Result<Vec<TotalInstitutionalInvestors>, TwseError> {
-    let mut request = ureq::get(INVESTORS_LINK).query("response", "json");
+    if args.market == Market::Tpex {
+        return get_tpex_total_institution_investors(args, options.language);
+    }
+
+    let mut request = ureq::get(INVESTORS_LINK);
     let date = format!("{}", args.date.format("%Y%m%d"));
 
     match args.date_type {
@@ -66,14 +117,99 @@ fn get_total_institution_investors(
         DateType::Month => request = request.query("monthDate", &date).query("type", "month"),
     }
 
-    let response: Response = request.call()?.into_json()?;
+    let response = get_response(
+        request,
+        options,
+        args.date,
+        &TOTAL_INSTITUTIONAL_INVESTORS_HEADERS,
+    )?;
+
+    let mut ret: Vec<TotalInstitutionalInvestors> = Vec::with_capacity(response.data.len());
+    for data in response.data {
+        if let Data::TotalInstitutionalInvestors(d) = data {
+            ret.push(d);
+        } else {
+            return Err(TwseError::EnumCastingError);
+        }
+    }
+
+    Ok(ret)
+}
+
+// the fields are checked against headers, which also locate the table of a csv response
+fn get_response(
+    request: ureq::Request,
+    options: RequestOptions,
+    date: NaiveDate,
+    headers: &[Header],
+) -> Result<Response, TwseError> {
+    let mut request = request.query("response", options.format.value());
+    if options.language == Language::English {
+        request = request.query("lang", options.language.value());
+    }
+
+    let response: Response = match options.format {
+        Format::Json => request.call()?.into_json()?,
+        Format::Csv => {
+            let body = encoding::read_response(request.call()?)?;
+            csv_response::decode(&body, date, headers)?
+        }
+    };
     if response.stat != TWSE_STATE_OK {
         return Err(TwseError::TWSEError(response.stat));
     }
+    response.language(headers)?;
+
+    Ok(response)
+}
+
+fn get_tpex_response(request: ureq::Request) -> Result<TpexResponse, TwseError> {
+    let response: TpexResponse = request.call()?.into_json()?;
+    if response.data.is_empty() {
+        return Err(TwseError::TWSEError(NO_DATA.to_string()));
+    }
+
+    Ok(response)
+}
+
+pub(crate) fn get_tpex_single_stock_institutional_investors(
+    args: TpexSingleStockInstitutionalInvestorsArgs,
+    language: Language,
+) -> Result<Vec<SingleStockInstitutionalInvestors>, TwseError> {
+    let request = ureq::get(TPEX_SINGLE_INVESTORS_LINK)
+        .query("l", language.tpex_value())
+        .query("o", "json")
+        .query("se", args.industry_type.value())
+        .query("t", args.date_type.tpex_value())
+        .query("d", &format_roc_date(args.date));
+    let response = get_tpex_response(request)?;
+
+    let mut ret: Vec<SingleStockInstitutionalInvestors> = Vec::with_capacity(response.data.len());
+    for data in response.data {
+        if let TpexData::SingleStockInstitutionalInvestors(d) = data {
+            ret.push(d.into_single_stock());
+        } else {
+            return Err(TwseError::EnumCastingError);
+        }
+    }
+
+    Ok(ret)
+}
+
+fn get_tpex_total_institution_investors(
+    args: TotalInstitutionInvestorsArgs,
+    language: Language,
+) -> Result<Vec<TotalInstitutionalInvestors>, TwseError> {
+    let request = ureq::get(TPEX_INVESTORS_LINK)
+        .query("l", language.tpex_value())
+        .query("o", "json")
+        .query("t", args.date_type.tpex_value())
+        .query("d", &format_roc_date(args.date));
+    let response = get_tpex_response(request)?;
 
     let mut ret: Vec<TotalInstitutionalInvestors> = Vec::with_capacity(response.data.len());
     for data in response.data {
-        if let Data::TotalInstitutionalInvestors(d) = data {
+        if let TpexData::TotalInstitutionalInvestors(d) = data {
             ret.push(d);
         } else {
             return Err(TwseError::EnumCastingError);
@@ -83,11 +219,346 @@ fn get_total_institution_investors(
     Ok(ret)
 }
 
-#[cfg(test)]
-use chrono::NaiveDate;
+fn get_institutional_investors_ranking<T: DeserializeOwned>(
+    link: &str,
+    args: InstitutionalInvestorsRankingArgs,
+) -> Result<Vec<T>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(link)
+        .query("response", "json")
+        .query("date", &date)
+        .query("type", args.date_type.value());
+
+    let response: ReportResponse<T> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_foreign_investors_ranking(
+    args: InstitutionalInvestorsRankingArgs,
+) -> Result<Vec<ForeignInvestorsRanking>, TwseError> {
+    get_institutional_investors_ranking(FOREIGN_INVESTORS_RANKING_LINK, args)
+}
+
+pub(crate) fn get_investment_trust_ranking(
+    args: InstitutionalInvestorsRankingArgs,
+) -> Result<Vec<InvestmentTrustRanking>, TwseError> {
+    get_institutional_investors_ranking(INVESTMENT_TRUST_RANKING_LINK, args)
+}
+
+pub(crate) fn get_dealers_ranking(
+    args: InstitutionalInvestorsRankingArgs,
+) -> Result<Vec<DealersRanking>, TwseError> {
+    get_institutional_investors_ranking(DEALERS_RANKING_LINK, args)
+}
+
+fn get_day_trading_response(args: DayTradingArgs) -> Result<DayTradingResponse, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(DAY_TRADING_LINK)
+        .query("response", "json")
+        .query("date", &date)
+        .query("selectType", "All");
+
+    let response: DayTradingResponse = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response)
+}
+
+pub(crate) fn get_day_trading(args: DayTradingArgs) -> Result<Vec<DayTrading>, TwseError> {
+    Ok(get_day_trading_response(args)?.stocks)
+}
+
+pub(crate) fn get_day_trading_summary(
+    args: DayTradingArgs,
+) -> Result<DayTradingSummary, TwseError> {
+    get_day_trading_response(args)?
+        .data
+        .into_iter()
+        .next()
+        .ok_or_else(|| TwseError::TWSEError("empty day trading summary".to_string()))
+}
+
+pub(crate) fn get_short_sale_balances(
+    args: ShortSaleBalancesArgs,
+) -> Result<Vec<ShortSaleBalances>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(SHORT_SALE_BALANCES_LINK)
+        .query("response", "json")
+        .query("date", &date);
+
+    let response: ReportResponse<ShortSaleBalances> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_securities_lending_transactions(
+    args: SecuritiesLendingArgs,
+) -> Result<Vec<SecuritiesLendingTransaction>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(SECURITIES_LENDING_LINK)
+        .query("response", "json")
+        .query("date", &date);
+
+    let response: ReportResponse<SecuritiesLendingTransaction> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_block_trades(args: BlockTradeArgs) -> Result<Vec<BlockTrade>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(BLOCK_TRADE_LINK)
+        .query("response", "json")
+        .query("date", &date)
+        .query("selectType", "S");
+
+    let response: ReportResponse<BlockTrade> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+fn get_after_hours_trading<T: DeserializeOwned>(
+    link: &str,
+    args: AfterHoursTradingArgs,
+) -> Result<Vec<T>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(link)
+        .query("response", "json")
+        .query("date", &date)
+        .query("selectType", "ALL");
+
+    let response: ReportResponse<T> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_fixed_price_trading(
+    args: AfterHoursTradingArgs,
+) -> Result<Vec<FixedPriceTrading>, TwseError> {
+    get_after_hours_trading(FIXED_PRICE_TRADING_LINK, args)
+}
+
+pub(crate) fn get_odd_lot_trading(
+    args: AfterHoursTradingArgs,
+) -> Result<Vec<OddLotTrading>, TwseError> {
+    get_after_hours_trading(ODD_LOT_TRADING_LINK, args)
+}
+
+pub(crate) fn get_market_daily_summaries(
+    args: MarketDailySummaryArgs,
+) -> Result<Vec<MarketDailySummary>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(MARKET_DAILY_SUMMARY_LINK)
+        .query("response", "json")
+        .query("date", &date);
+
+    let response: ReportResponse<MarketDailySummary> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_intraday_market_statistics(
+    args: IntradayMarketStatisticsArgs,
+) -> Result<Vec<IntradayMarketStatistics>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(INTRADAY_MARKET_STATISTICS_LINK)
+        .query("response", "json")
+        .query("date", &date);
+
+    let response: ReportResponse<IntradayMarketStatistics> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_taiex_history(args: TaiexHistoryArgs) -> Result<Vec<TaiexHistory>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(TAIEX_HISTORY_LINK)
+        .query("response", "json")
+        .query("date", &date);
+
+    let response: ReportResponse<TaiexHistory> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_sector_indices(
+    args: SectorIndicesArgs,
+) -> Result<HashMap<Index, SectorIndex>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(SECTOR_INDICES_LINK)
+        .query("response", "json")
+        .query("date", &date)
+        .query("type", "IND");
+
+    let response: SectorIndexResponse = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    // indices like 寶島股價指數 do not map to any sector and are left out
+    let mut ret: HashMap<Index, SectorIndex> = HashMap::with_capacity(response.data1.len());
+    for data in response.data1 {
+        if let Some(index) = data.index() {
+            ret.insert(index, data);
+        }
+    }
+
+    Ok(ret)
+}
+
+fn get_ex_rights<T: DeserializeOwned>(link: &str, args: ExRightsArgs) -> Result<Vec<T>, TwseError> {
+    let start_date = format!("{}", args.start_date.format("%Y%m%d"));
+    let end_date = format!("{}", args.end_date.format("%Y%m%d"));
+    let request = ureq::get(link)
+        .query("response", "json")
+        .query("strDate", &start_date)
+        .query("endDate", &end_date);
+
+    let response: ReportResponse<T> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+// only the latest trading day is served
+pub(crate) fn get_daily_quotes() -> Result<Vec<DailyQuote>, TwseError> {
+    let request = ureq::get(DAILY_QUOTES_LINK).query("response", "json");
+
+    let response: DailyQuoteResponse = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_foreign_holdings(
+    args: ForeignHoldingsArgs,
+) -> Result<Vec<ForeignHolding>, TwseError> {
+    let date = format!("{}", args.date.format("%Y%m%d"));
+    let request = ureq::get(FOREIGN_HOLDINGS_LINK)
+        .query("response", "json")
+        .query("date", &date)
+        .query("selectType", "ALLBUT0999");
+
+    let response: ForeignHoldingResponse = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_ex_rights_schedule(
+    args: ExRightsArgs,
+) -> Result<Vec<ExRightsSchedule>, TwseError> {
+    get_ex_rights(EX_RIGHTS_SCHEDULE_LINK, args)
+}
+
+pub(crate) fn get_ex_rights_results(args: ExRightsArgs) -> Result<Vec<ExRightsResult>, TwseError> {
+    get_ex_rights(EX_RIGHTS_RESULT_LINK, args)
+}
+
+fn get_corporate_events_response<T: DeserializeOwned>(
+    link: &str,
+    args: CorporateEventsArgs,
+) -> Result<Vec<T>, TwseError> {
+    let start_date = format!("{}", args.start_date.format("%Y%m%d"));
+    let end_date = format!("{}", args.end_date.format("%Y%m%d"));
+    let request = ureq::get(link)
+        .query("response", "json")
+        .query("strDate", &start_date)
+        .query("endDate", &end_date);
+
+    let response: ReportResponse<T> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_capital_reductions(
+    args: CorporateEventsArgs,
+) -> Result<Vec<CapitalReduction>, TwseError> {
+    get_corporate_events_response(CAPITAL_REDUCTION_LINK, args)
+}
+
+pub(crate) fn get_trading_suspensions(
+    args: CorporateEventsArgs,
+) -> Result<Vec<TradingSuspension>, TwseError> {
+    get_corporate_events_response(TRADING_SUSPENSION_LINK, args)
+}
+
+pub(crate) fn get_par_value_changes(
+    args: CorporateEventsArgs,
+) -> Result<Vec<ParValueChange>, TwseError> {
+    get_corporate_events_response(PAR_VALUE_CHANGE_LINK, args)
+}
+
+fn get_announcement<T: DeserializeOwned>(
+    link: &str,
+    args: AnnouncementArgs,
+) -> Result<Vec<T>, TwseError> {
+    let start_date = format!("{}", args.start_date.format("%Y%m%d"));
+    let end_date = format!("{}", args.end_date.format("%Y%m%d"));
+    let request = ureq::get(link)
+        .query("response", "json")
+        .query("startDate", &start_date)
+        .query("endDate", &end_date);
+
+    let response: ReportResponse<T> = request.call()?.into_json()?;
+    if response.stat != TWSE_STATE_OK {
+        return Err(TwseError::TWSEError(response.stat));
+    }
+
+    Ok(response.data)
+}
+
+pub(crate) fn get_attention_stocks(
+    args: AnnouncementArgs,
+) -> Result<Vec<AttentionStock>, TwseError> {
+    get_announcement(ATTENTION_STOCKS_LINK, args)
+}
+
+pub(crate) fn get_disposition_stocks(
+    args: AnnouncementArgs,
+) -> Result<Vec<DispositionStock>, TwseError> {
+    get_announcement(DISPOSITION_STOCKS_LINK, args)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::args::IndustryType;
 
     #[test]
     fn test_get_single_stock_institutional_investors() {
@@ -99,6 +570,7 @@ mod tests {
                 SingleStockInstitutionalInvestorsArgs {
                     date: date,
                     date_type: DateType::Week,
+                    market: Market::Twse,
                     industry_type: IndustryType::Cement,
                 },
                 "week date_type should be ok",
@@ -108,6 +580,7 @@ mod tests {
                 SingleStockInstitutionalInvestorsArgs {
                     date: date,
                     date_type: DateType::Day,
+                    market: Market::Twse,
                     industry_type: IndustryType::Cement,
                 },
                 "day date_type should be ok",
@@ -117,6 +590,7 @@ mod tests {
                 SingleStockInstitutionalInvestorsArgs {
                     date: date,
                     date_type: DateType::Month,
+                    market: Market::Twse,
                     industry_type: IndustryType::Cement,
                 },
                 "month date_type should be ok",
@@ -126,6 +600,7 @@ mod tests {
                 SingleStockInstitutionalInvestorsArgs {
                     date: wrong_date,
                     date_type: DateType::Week,
+                    market: Market::Twse,
                     industry_type: IndustryType::Cement,
                 },
                 "week date_type should not be ok",
@@ -135,6 +610,7 @@ mod tests {
                 SingleStockInstitutionalInvestorsArgs {
                     date: wrong_date,
                     date_type: DateType::Day,
+                    market: Market::Twse,
                     industry_type: IndustryType::Cement,
                 },
                 "day date_type should not be ok",
@@ -144,19 +620,81 @@ mod tests {
                 SingleStockInstitutionalInvestorsArgs {
                     date: wrong_date,
                     date_type: DateType::Month,
+                    market: Market::Twse,
                     industry_type: IndustryType::Cement,
                 },
                 "month date_type should not be ok",
                 false,
             ),
+            (
+                SingleStockInstitutionalInvestorsArgs {
+                    date: date,
+                    date_type: DateType::Day,
+                    market: Market::Tpex,
+                    industry_type: IndustryType::Semiconductor,
+                },
+                "tpex day date_type should be ok",
+                true,
+            ),
+            (
+                SingleStockInstitutionalInvestorsArgs {
+                    date: date,
+                    date_type: DateType::Day,
+                    market: Market::Tpex,
+                    industry_type: IndustryType::Cement,
+                },
+                "tpex has no cement industry",
+                false,
+            ),
+            (
+                SingleStockInstitutionalInvestorsArgs {
+                    date: wrong_date,
+                    date_type: DateType::Day,
+                    market: Market::Tpex,
+                    industry_type: IndustryType::Semiconductor,
+                },
+                "tpex day date_type should not be ok",
+                false,
+            ),
         ];
 
         for (input, description, want) in test_cases {
-            let got = get_single_stock_institutional_investors(input);
+            let got = get_single_stock_institutional_investors_with_options(
+                input,
+                RequestOptions::default(),
+            );
             assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
         }
     }
 
+    #[test]
+    fn test_tpex_unsupported_industry() {
+        let got = get_single_stock_institutional_investors_with_options(
+            SingleStockInstitutionalInvestorsArgs {
+                date: NaiveDate::from_ymd_opt(2021, 2, 5).unwrap(),
+                date_type: DateType::Day,
+                market: Market::Tpex,
+                industry_type: IndustryType::Cement,
+            },
+            RequestOptions::default(),
+        );
+        assert!(matches!(got, Err(TwseError::IndustryError(_))));
+        assert!(!got.unwrap_err().is_no_data());
+    }
+
+    #[test]
+    fn test_get_tpex_single_stock_institutional_investors() {
+        let got = get_tpex_single_stock_institutional_investors(
+            TpexSingleStockInstitutionalInvestorsArgs {
+                date: NaiveDate::from_ymd_opt(2021, 2, 5).unwrap(),
+                date_type: DateType::Day,
+                industry_type: TpexIndustryType::CulturalCreative,
+            },
+            Language::Chinese,
+        );
+        assert!(got.is_ok(), "[tpex only industry should be ok]");
+    }
+
     #[test]
     fn test_get_total_institution_investors() {
         let date = NaiveDate::parse_from_str("2021-02-01", "%Y-%m-%d").unwrap();
@@ -167,6 +705,7 @@ mod tests {
                 TotalInstitutionInvestorsArgs {
                     date: date,
                     date_type: DateType::Week,
+                    market: Market::Twse,
                 },
                 "week date_type should be ok",
                 true,
@@ -175,6 +714,7 @@ mod tests {
                 TotalInstitutionInvestorsArgs {
                     date: date,
                     date_type: DateType::Day,
+                    market: Market::Twse,
                 },
                 "day date_type should be ok",
                 true,
@@ -183,6 +723,7 @@ mod tests {
                 TotalInstitutionInvestorsArgs {
                     date: date,
                     date_type: DateType::Month,
+                    market: Market::Twse,
                 },
                 "month date_type should be ok",
                 true,
@@ -191,6 +732,7 @@ mod tests {
                 TotalInstitutionInvestorsArgs {
                     date: wrong_date,
                     date_type: DateType::Week,
+                    market: Market::Twse,
                 },
                 "week date_type should not be ok",
                 false,
@@ -199,6 +741,7 @@ mod tests {
                 TotalInstitutionInvestorsArgs {
                     date: wrong_date,
                     date_type: DateType::Day,
+                    market: Market::Twse,
                 },
                 "day date_type should not be ok",
                 false,
@@ -207,15 +750,428 @@ mod tests {
                 TotalInstitutionInvestorsArgs {
                     date: wrong_date,
                     date_type: DateType::Month,
+                    market: Market::Twse,
                 },
                 "month date_type should not be ok",
                 false,
             ),
+            (
+                TotalInstitutionInvestorsArgs {
+                    date: date,
+                    date_type: DateType::Day,
+                    market: Market::Tpex,
+                },
+                "tpex day date_type should be ok",
+                true,
+            ),
+            (
+                TotalInstitutionInvestorsArgs {
+                    date: wrong_date,
+                    date_type: DateType::Day,
+                    market: Market::Tpex,
+                },
+                "tpex day date_type should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got =
+                get_total_institution_investors_with_options(input, RequestOptions::default());
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+
+    #[test]
+    fn test_get_institutional_investors_ranking() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                InstitutionalInvestorsRankingArgs {
+                    date: date,
+                    date_type: DateType::Day,
+                },
+                "day date_type should be ok",
+                true,
+            ),
+            (
+                InstitutionalInvestorsRankingArgs {
+                    date: wrong_date,
+                    date_type: DateType::Day,
+                },
+                "day date_type should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_foreign_investors_ranking(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[foreign {}]: input:{:?}",
+                description,
+                input
+            );
+            let got = get_investment_trust_ranking(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[trust {}]: input:{:?}",
+                description,
+                input
+            );
+            let got = get_dealers_ranking(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[dealers {}]: input:{:?}",
+                description,
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_day_trading() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (DayTradingArgs { date: date }, "date should be ok", true),
+            (
+                DayTradingArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_day_trading(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+            let got = get_day_trading_summary(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[summary {}]: input:{:?}",
+                description,
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_short_sale_balances() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                ShortSaleBalancesArgs { date: date },
+                "date should be ok",
+                true,
+            ),
+            (
+                ShortSaleBalancesArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
         ];
 
         for (input, description, want) in test_cases {
-            let got = get_total_institution_investors(input);
+            let got = get_short_sale_balances(input);
             assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
         }
     }
+
+    #[test]
+    fn test_get_securities_lending_transactions() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                SecuritiesLendingArgs { date: date },
+                "date should be ok",
+                true,
+            ),
+            (
+                SecuritiesLendingArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_securities_lending_transactions(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+
+    #[test]
+    fn test_get_block_trades() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (BlockTradeArgs { date: date }, "date should be ok", true),
+            (
+                BlockTradeArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_block_trades(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+
+    #[test]
+    fn test_get_after_hours_trading() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                AfterHoursTradingArgs { date: date },
+                "date should be ok",
+                true,
+            ),
+            (
+                AfterHoursTradingArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_fixed_price_trading(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[fixed price {}]: input:{:?}",
+                description,
+                input
+            );
+            let got = get_odd_lot_trading(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[odd lot {}]: input:{:?}",
+                description,
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_market_daily_summaries() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                MarketDailySummaryArgs { date: date },
+                "date should be ok",
+                true,
+            ),
+            (
+                MarketDailySummaryArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_market_daily_summaries(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+
+    #[test]
+    fn test_get_intraday_market_statistics() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                IntradayMarketStatisticsArgs { date: date },
+                "date should be ok",
+                true,
+            ),
+            (
+                IntradayMarketStatisticsArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_intraday_market_statistics(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+
+    #[test]
+    fn test_get_taiex_history() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (TaiexHistoryArgs { date: date }, "date should be ok", true),
+            (
+                TaiexHistoryArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_taiex_history(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+
+    #[test]
+    fn test_get_sector_indices() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (SectorIndicesArgs { date: date }, "date should be ok", true),
+            (
+                SectorIndicesArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_sector_indices(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+
+    #[test]
+    fn test_get_daily_quotes() {
+        assert!(get_daily_quotes().is_ok());
+    }
+
+    #[test]
+    fn test_get_foreign_holdings() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                ForeignHoldingsArgs { date: date },
+                "date should be ok",
+                true,
+            ),
+            (
+                ForeignHoldingsArgs { date: wrong_date },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_foreign_holdings(input);
+            assert_eq!(want, got.is_ok(), "[{}]: input:{:?}", description, input);
+        }
+    }
+
+    #[test]
+    fn test_get_ex_rights() {
+        let start_date = NaiveDate::parse_from_str("2021-01-04", "%Y-%m-%d").unwrap();
+        let end_date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                ExRightsArgs {
+                    start_date: start_date,
+                    end_date: end_date,
+                },
+                "date range should be ok",
+                true,
+            ),
+            (
+                ExRightsArgs {
+                    start_date: wrong_date,
+                    end_date: wrong_date,
+                },
+                "date range should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_ex_rights_schedule(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[schedule {}]: input:{:?}",
+                description,
+                input
+            );
+            let got = get_ex_rights_results(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[result {}]: input:{:?}",
+                description,
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_announcements() {
+        let start_date = NaiveDate::parse_from_str("2021-02-01", "%Y-%m-%d").unwrap();
+        let end_date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                AnnouncementArgs {
+                    start_date: start_date,
+                    end_date: end_date,
+                },
+                "date range should be ok",
+                true,
+            ),
+            (
+                AnnouncementArgs {
+                    start_date: wrong_date,
+                    end_date: wrong_date,
+                },
+                "date range should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_attention_stocks(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[attention {}]: input:{:?}",
+                description,
+                input
+            );
+            let got = get_disposition_stocks(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[disposition {}]: input:{:?}",
+                description,
+                input
+            );
+        }
+    }
 }
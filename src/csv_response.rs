@@ -0,0 +1,226 @@
+use crate::errors::{TwseError, NO_DATA};
+use crate::schema::{headers_language, Data, Header, Response};
+use chrono::NaiveDate;
+use serde::de::value::{Error as ValueError, SeqDeserializer};
+use serde::Deserialize;
+
+const TWSE_STATE_OK: &str = "OK";
+
+// decodes a response=csv body into the same Response as the json one,
+// the table is located by its header row and the lines around it are skipped
+pub(crate) fn decode(
+    body: &str,
+    date: NaiveDate,
+    headers: &[Header],
+) -> Result<Response, TwseError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(body.as_bytes());
+
+    let mut title: Option<String> = None;
+    let mut fields: Option<Vec<String>> = None;
+    let mut data: Vec<Data> = vec![];
+    let mut trailing_separator = false;
+    for record in reader.records() {
+        let mut cells = clean_cells(&record?);
+        if cells.iter().all(|cell| cell.is_empty()) {
+            if fields.is_some() {
+                break;
+            }
+            continue;
+        }
+
+        match fields {
+            None => {
+                let separator = strip_separator(&mut cells, headers.len());
+                if headers_language(&cells, headers).is_some() {
+                    trailing_separator = separator;
+                    fields = Some(cells);
+                } else if title.is_none() {
+                    title = Some(cells.join(" "));
+                }
+            }
+            Some(_) => {
+                if trailing_separator {
+                    strip_separator(&mut cells, headers.len());
+                }
+                // the notes after the table have less columns
+                if cells.len() != headers.len() {
+                    break;
+                }
+                let row =
+                    Data::deserialize(SeqDeserializer::<_, ValueError>::new(cells.into_iter()))
+                        .map_err(|_| TwseError::EnumCastingError)?;
+                data.push(row);
+            }
+        }
+    }
+
+    match fields {
+        Some(fields) => Ok(Response {
+            stat: TWSE_STATE_OK.to_string(),
+            date,
+            title: title.unwrap_or_default(),
+            fields,
+            data,
+        }),
+        None => Err(TwseError::TWSEError(
+            title.unwrap_or_else(|| NO_DATA.to_string()),
+        )),
+    }
+}
+
+// stock ids are written as ="0050" to keep the leading zeros in spreadsheets
+fn clean_cells(record: &csv::StringRecord) -> Vec<String> {
+    record
+        .iter()
+        .map(|cell| {
+            let cell = cell.trim();
+            match cell.strip_prefix("=\"").and_then(|c| c.strip_suffix('"')) {
+                Some(cell) => cell.to_string(),
+                None => cell.to_string(),
+            }
+        })
+        .collect()
+}
+
+// most TWSE csv lines end with a comma, which reads as one more empty column,
+// removes it and tells whether it was there
+fn strip_separator(cells: &mut Vec<String>, columns: usize) -> bool {
+    if cells.len() == columns + 1 && cells.last().is_some_and(|cell| cell.is_empty()) {
+        cells.pop();
+        return true;
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding;
+    use crate::schema::{
+        SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS, TOTAL_INSTITUTIONAL_INVESTORS_HEADERS,
+    };
+
+    const TOTAL_CSV: &str = "\"110年02月05日 三大法人買賣金額統計表\"\r\n\
+\"單位名稱\",\"買進金額\",\"賣出金額\",\"買賣差額\",\r\n\
+\"自營商(自行買賣)\",\"3,720,692,397\",\"2,657,469,622\",\"1,063,222,775\",\r\n\
+\"投信\",\"2,305,132,480\",\"1,693,938,980\",\"611,193,500\",\r\n\
+\"合計\",\"87,532,849,369\",\"89,714,793,209\",\"-2,181,943,840\",\r\n\
+\r\n\
+\"說明:\"\r\n\
+\"本統計資訊含一般、零股、盤後定價、鉅額，不含拍賣、標購。\"\r\n";
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, 2, 5).unwrap()
+    }
+
+    fn assert_total_response(response: &Response) {
+        assert_eq!(response.stat, "OK");
+        assert_eq!(response.date, date());
+        assert_eq!(response.title, "110年02月05日 三大法人買賣金額統計表");
+        assert_eq!(response.fields.len(), 4);
+        assert_eq!(response.data.len(), 3);
+        for (n, data) in response.data.iter().enumerate() {
+            assert!(
+                matches!(data, Data::TotalInstitutionalInvestors(_)),
+                "data vector index {} cannot cast out",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_utf8_with_bom() {
+        let mut body = vec![0xEF, 0xBB, 0xBF];
+        body.extend_from_slice(TOTAL_CSV.as_bytes());
+
+        let body = encoding::decode(&body, None);
+        let response = decode(&body, date(), &TOTAL_INSTITUTIONAL_INVESTORS_HEADERS).unwrap();
+        assert_total_response(&response);
+    }
+
+    #[test]
+    fn test_decode_big5() {
+        let (body, _, had_errors) = encoding_rs::BIG5.encode(TOTAL_CSV);
+        assert!(!had_errors);
+
+        let body = encoding::decode(&body, None);
+        let response = decode(&body, date(), &TOTAL_INSTITUTIONAL_INVESTORS_HEADERS).unwrap();
+        assert_total_response(&response);
+    }
+
+    #[test]
+    fn test_decode_with_quoted_stock_ids() {
+        let body = "\"110年02月05日 三大法人買賣超日報\"\n\
+\"證券代號\",\"證券名稱\",\"外陸資買進股數(不含外資自營商)\",\"外陸資賣出股數(不含外資自營商)\",\"外陸資買賣超股數(不含外資自營商)\",\"外資自營商買進股數\",\"外資自營商賣出股數\",\"外資自營商買賣超股數\",\"投信買進股數\",\"投信賣出股數\",\"投信買賣超股數\",\"自營商買賣超股數\",\"自營商買進股數(自行買賣)\",\"自營商賣出股數(自行買賣)\",\"自營商買賣超股數(自行買賣)\",\"自營商買進股數(避險)\",\"自營商賣出股數(避險)\",\"自營商買賣超股數(避險)\",\"三大法人買賣超股數\",\n\
+=\"0050\",\"元大台灣50      \",\"1,316,473\",\"1,079,050\",\"237,423\",\"0\",\"0\",\"0\",\"0\",\"0\",\"0\",\"3,000\",\"3,000\",\"0\",\"3,000\",\"0\",\"0\",\"0\",\"240,423\",\n\
+\"說明:\"\n";
+
+        let response = decode(body, date(), &SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS).unwrap();
+        assert_eq!(response.data.len(), 1);
+        assert!(matches!(
+            response.data[0],
+            Data::SingleStockInstitutionalInvestors(_)
+        ));
+    }
+
+    #[test]
+    fn test_decode_without_table() {
+        let got = decode("", date(), &TOTAL_INSTITUTIONAL_INVESTORS_HEADERS);
+        assert!(matches!(got, Err(TwseError::TWSEError(_))));
+
+        let body = "\"很抱歉，沒有符合條件的資料!\"\r\n";
+        match decode(body, date(), &TOTAL_INSTITUTIONAL_INVESTORS_HEADERS) {
+            Err(TwseError::TWSEError(stat)) => assert_eq!(stat, "很抱歉，沒有符合條件的資料!"),
+            got => assert!(false, "unexpected {:?}", got),
+        }
+    }
+
+    #[test]
+    fn test_decode_without_trailing_separator() {
+        let body = TOTAL_CSV.replace(",\r\n", "\r\n");
+        let response = decode(&body, date(), &TOTAL_INSTITUTIONAL_INVESTORS_HEADERS).unwrap();
+        assert_total_response(&response);
+    }
+
+    #[test]
+    fn test_strip_separator() {
+        let cells =
+            |cells: &[&str]| -> Vec<String> { cells.iter().map(|c| c.to_string()).collect() };
+        let test_cases = vec![
+            (
+                cells(&["a", "b", ""]),
+                cells(&["a", "b"]),
+                true,
+                "trailing separator",
+            ),
+            (
+                cells(&["a", ""]),
+                cells(&["a", ""]),
+                false,
+                "empty last cell",
+            ),
+            (
+                cells(&["a", "", ""]),
+                cells(&["a", ""]),
+                true,
+                "empty last cell and separator",
+            ),
+            (
+                cells(&["a", "b", "c"]),
+                cells(&["a", "b", "c"]),
+                false,
+                "one more column",
+            ),
+        ];
+
+        for (mut input, want, want_stripped, description) in test_cases {
+            let stripped = strip_separator(&mut input, 2);
+            assert_eq!(want_stripped, stripped, "[{}]", description);
+            assert_eq!(want, input, "[{}]", description);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::errors::TwseError;
+use encoding_rs::{DecoderResult, Encoding, BIG5, UTF_8};
+use std::io::Read;
+
+// html pages declare their charset in a meta tag near the top
+const META_SNIFF_LENGTH: usize = 1024;
+// the user-defined areas of CP950 and where Microsoft maps them in the private use area,
+// TWSE uses them for the rare characters of some company names
+const EUDC_RANGES: [(u8, u8, u32); 4] = [
+    (0xFA, 0xFE, 0xE000),
+    (0x8E, 0xA0, 0xE311),
+    (0x81, 0x8D, 0xEEB8),
+    // C6A1 is the first one, as C640 to C67E are standard characters
+    (0xC6, 0xC8, 0xF6B1 - 63),
+];
+const EUDC_TRAILS_PER_LEAD: u32 = 157;
+
+// reads the body and transcodes it into UTF-8, the encoding comes from
+// the BOM, the content-type header, the html meta tag, and lastly a guess
+pub(crate) fn read_response(response: ureq::Response) -> Result<String, TwseError> {
+    let charset = response
+        .header("content-type")
+        .and_then(charset_of)
+        .map(|charset| charset.to_string());
+
+    let mut body: Vec<u8> = vec![];
+    response.into_reader().read_to_end(&mut body)?;
+
+    Ok(decode(&body, charset.as_deref()))
+}
+
+pub(crate) fn decode(body: &[u8], charset: Option<&str>) -> String {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(body) {
+        return decode_with(encoding, &body[bom_length..]);
+    }
+
+    let declared = charset
+        .or_else(|| sniff_meta_charset(body))
+        .and_then(encoding_for_label);
+    match declared {
+        // TWSE labels some Big5 pages as UTF-8, so it has to be checked
+        Some(encoding) if encoding == UTF_8 => decode_guessed(body),
+        Some(encoding) => decode_with(encoding, body),
+        None => decode_guessed(body),
+    }
+}
+
+fn decode_guessed(body: &[u8]) -> String {
+    match std::str::from_utf8(body) {
+        Ok(text) => text.to_string(),
+        Err(_) => decode_with(BIG5, body),
+    }
+}
+
+fn decode_with(encoding: &'static Encoding, body: &[u8]) -> String {
+    if encoding == BIG5 {
+        return decode_big5(body);
+    }
+
+    encoding.decode_without_bom_handling(body).0.into_owned()
+}
+
+// the WHATWG Big5 covers CP950 and HKSCS, the byte pairs left are the
+// user-defined ones which are kept as private use characters instead of U+FFFD
+fn decode_big5(body: &[u8]) -> String {
+    let mut decoder = BIG5.new_decoder_without_bom_handling();
+    let mut ret = String::with_capacity(body.len() * 3 / 2);
+    let mut input = body;
+
+    loop {
+        if let Some(length) = decoder.max_utf8_buffer_length_without_replacement(input.len()) {
+            ret.reserve(length);
+        }
+        let (result, mut read) =
+            decoder.decode_to_string_without_replacement(input, &mut ret, true);
+        match result {
+            DecoderResult::InputEmpty => break,
+            DecoderResult::OutputFull => {}
+            DecoderResult::Malformed(length, extra) => {
+                let end = read - extra as usize;
+                let mut sequence = input[end - length as usize..end].to_vec();
+                // an ASCII trail byte is not consumed but left for the next character
+                if let (1, 0, Some(&trail)) = (length, extra, input.get(read)) {
+                    if eudc_char(&[sequence[0], trail]).is_some() {
+                        sequence.push(trail);
+                        read += 1;
+                    }
+                }
+                ret.push(eudc_char(&sequence).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+        }
+        input = &input[read..];
+    }
+
+    ret
+}
+
+fn eudc_char(sequence: &[u8]) -> Option<char> {
+    let (lead, trail) = match *sequence {
+        [lead, trail] => (lead, trail),
+        _ => return None,
+    };
+    let trail_index = match trail {
+        0x40..=0x7E => trail - 0x40,
+        0xA1..=0xFE => trail - 0x62,
+        _ => return None,
+    };
+
+    EUDC_RANGES
+        .iter()
+        .find(|(first, last, _)| (*first..=*last).contains(&lead))
+        .and_then(|(first, _, base)| {
+            let offset = (lead - first) as u32 * EUDC_TRAILS_PER_LEAD + trail_index as u32;
+            char::from_u32(base + offset)
+        })
+}
+
+fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    match label.trim().to_ascii_lowercase().as_str() {
+        // Microsoft names which are not WHATWG labels
+        "cp950" | "ms950" | "windows-950" | "x-windows-950" | "big5-2003" => Some(BIG5),
+        label => Encoding::for_label(label.as_bytes()),
+    }
+}
+
+// e.g. text/html; charset=MS950
+fn charset_of(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("charset") {
+            Some(value.trim().trim_matches('"'))
+        } else {
+            None
+        }
+    })
+}
+
+// e.g. <meta http-equiv="Content-Type" content="text/html; charset=big5">
+fn sniff_meta_charset(body: &[u8]) -> Option<&str> {
+    let head = &body[..body.len().min(META_SNIFF_LENGTH)];
+    let position = head
+        .windows(b"charset=".len())
+        .position(|window| window.eq_ignore_ascii_case(b"charset="))?;
+    let value = &head[position + b"charset=".len()..];
+    let value = value.strip_prefix(b"\"").unwrap_or(value);
+    let end = value
+        .iter()
+        .position(|b| !(b.is_ascii_alphanumeric() || *b == b'-' || *b == b'_'))
+        .unwrap_or(value.len());
+
+    std::str::from_utf8(&value[..end]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let text = "台積電,環泥,碁富";
+        let (big5, _, had_errors) = BIG5.encode(text);
+        assert!(!had_errors);
+        let mut utf8_bom = vec![0xEF, 0xBB, 0xBF];
+        utf8_bom.extend_from_slice(text.as_bytes());
+
+        let test_cases: Vec<(&[u8], Option<&str>, &str)> = vec![
+            (text.as_bytes(), None, "utf-8 should be kept"),
+            (&utf8_bom, Some("big5"), "bom should win over the header"),
+            (&big5, Some("MS950"), "cp950 label should be big5"),
+            (&big5, Some("big5"), "big5 label should be big5"),
+            (&big5, Some("utf-8"), "mislabeled big5 should be guessed"),
+            (&big5, None, "big5 should be guessed"),
+        ];
+
+        for (input, charset, description) in test_cases {
+            assert_eq!(decode(input, charset), text, "[{}]", description);
+        }
+    }
+
+    #[test]
+    fn test_decode_with_meta_charset() {
+        let mut html = b"<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=big5\"></head><body>".to_vec();
+        html.extend_from_slice(&BIG5.encode("台泥").0);
+        html.extend_from_slice(b"</body></html>");
+
+        assert_eq!(sniff_meta_charset(&html), Some("big5"));
+        assert!(decode(&html, None).contains("<body>台泥</body>"));
+    }
+
+    #[test]
+    fn test_decode_big5_extensions() {
+        // 碁 is an ETEN extension, 8140 and 81A1 are user-defined and not in HKSCS
+        let body = [0xF9, 0xD6, 0x81, 0x40, 0x81, 0xA1, b'A', 0xFF];
+        let got: Vec<char> = decode(&body, Some("big5")).chars().collect();
+
+        assert_eq!(
+            got,
+            vec![
+                '碁',
+                '\u{EEB8}',
+                '\u{EEF7}',
+                'A',
+                char::REPLACEMENT_CHARACTER
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eudc_char() {
+        assert_eq!(eudc_char(&[0xFA, 0x40]), Some('\u{E000}'));
+        assert_eq!(eudc_char(&[0xFA, 0xA1]), Some('\u{E03F}'));
+        assert_eq!(eudc_char(&[0xFE, 0xFE]), Some('\u{E310}'));
+        assert_eq!(eudc_char(&[0x8E, 0x40]), Some('\u{E311}'));
+        assert_eq!(eudc_char(&[0x81, 0x40]), Some('\u{EEB8}'));
+        assert_eq!(eudc_char(&[0xC6, 0xA1]), Some('\u{F6B1}'));
+        assert_eq!(eudc_char(&[0xC8, 0xFE]), Some('\u{F848}'));
+        assert_eq!(eudc_char(&[0xA4, 0x40]), None);
+        assert_eq!(eudc_char(&[0xFA, 0x20]), None);
+        assert_eq!(eudc_char(&[0xFA]), None);
+    }
+
+    #[test]
+    fn test_charset_of() {
+        assert_eq!(charset_of("text/html; charset=MS950"), Some("MS950"));
+        assert_eq!(charset_of("text/csv;Charset=\"big5\""), Some("big5"));
+        assert_eq!(charset_of("application/json"), None);
+    }
+}
@@ -1,5 +1,11 @@
 use thiserror::Error;
 
+// the stat of TWSE when a query has no rows, e.g. on a holiday, TPEx, the OpenAPI and
+// csv responses have no stat and are given this one when they come back empty
+pub(crate) const NO_DATA: &str = "很抱歉，沒有符合條件的資料!";
+// the TAIFEX counterpart
+pub(crate) const TAIFEX_NO_DATA: &str = "查無資料";
+
 #[allow(dead_code)]
 #[derive(Error, Debug)]
 pub enum TwseError {
@@ -9,6 +15,85 @@ pub enum TwseError {
     TWSEError(String),
     #[error("Cannot do ureq get")]
     UreqError(#[from] ureq::Error),
+    #[error("Industry {0} is not traded on the market")]
+    IndustryError(String),
+    #[error("Unexpected fields {0}")]
+    FieldsError(String),
+    #[error("Unexpected cell {0}")]
+    CellError(String),
+    #[error("The {0} format is not served by the endpoint")]
+    FormatError(String),
     #[error("Json decode failed")]
     JsonError(#[from] std::io::Error),
+    #[error("Csv decode failed")]
+    CsvError(#[from] csv::Error),
+    #[error("Cache access failed")]
+    CacheError(std::io::Error),
+    #[cfg(feature = "arrow")]
+    #[error("Arrow conversion failed")]
+    ArrowError(#[from] arrow::error::ArrowError),
+    #[cfg(feature = "arrow")]
+    #[error("Decimal {0} does not fit the column scale")]
+    DecimalError(String),
+    #[cfg(feature = "arrow")]
+    #[error("Integer {0} does not fit the column")]
+    IntegerError(String),
+    #[cfg(feature = "arrow")]
+    #[error("Parquet write failed")]
+    ParquetError(#[from] parquet::errors::ParquetError),
+    #[cfg(feature = "sqlite")]
+    #[error("Sqlite access failed")]
+    SqliteError(#[from] rusqlite::Error),
+    #[cfg(feature = "postgres")]
+    #[error("Postgres access failed")]
+    PostgresError(#[from] postgres::Error),
+    #[cfg(feature = "postgres")]
+    #[error("Key {0} is listed twice in the report")]
+    DuplicateKeyError(String),
+}
+
+impl TwseError {
+    // the query is fine but has no rows, e.g. a holiday, every other error is a real failure
+    pub fn is_no_data(&self) -> bool {
+        match self {
+            TwseError::TWSEError(stat) => {
+                stat.contains("沒有符合條件的資料")
+                    || stat.contains(TAIFEX_NO_DATA)
+                    || stat.to_lowercase().contains("no data")
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_no_data() {
+        let test_cases = vec![
+            (TwseError::TWSEError(NO_DATA.to_string()), true, "twse"),
+            (
+                TwseError::TWSEError(TAIFEX_NO_DATA.to_string()),
+                true,
+                "taifex",
+            ),
+            (
+                TwseError::TWSEError("Sorry, no data found!".to_string()),
+                true,
+                "english",
+            ),
+            (
+                TwseError::TWSEError("查詢日期大於今日，請重新查詢!".to_string()),
+                false,
+                "future date",
+            ),
+            (TwseError::EnumCastingError, false, "not a stat"),
+        ];
+
+        for (input, want, description) in test_cases {
+            assert_eq!(input.is_no_data(), want, "[{}]", description);
+        }
+    }
 }
@@ -1,4 +1,22 @@
-mod args;
+pub mod args;
+mod cell;
+pub mod client;
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod corporate_events;
 mod crawler;
-mod errors;
-mod schema;
+mod csv_response;
+mod encoding;
+pub mod errors;
+mod openapi;
+#[cfg(feature = "postgres")]
+pub mod postgres_sink;
+pub mod realtime;
+pub mod schema;
+pub mod security_master;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod taifex;
+#[cfg(test)]
+mod test_server;
+pub mod writer;
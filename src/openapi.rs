@@ -0,0 +1,119 @@
+use crate::errors::{TwseError, NO_DATA};
+use crate::schema::{
+    DailyQuote, ForeignHolding, MarketDailySummary, SingleStockInstitutionalInvestors,
+    TaiexHistory, TotalInstitutionalInvestors,
+};
+use serde::de::DeserializeOwned;
+
+pub const OPENAPI_LINK: &str = "https://openapi.twse.com.tw/v1";
+const MARKET_DAILY_SUMMARY_PATH: &str = "/exchangeReport/FMTQIK";
+const TAIEX_HISTORY_PATH: &str = "/indicesReport/MI_5MINS_HIST";
+const DAILY_QUOTES_PATH: &str = "/exchangeReport/STOCK_DAY_ALL";
+const FOREIGN_HOLDINGS_PATH: &str = "/fund/MI_QFIIS";
+const TOTAL_INSTITUTIONAL_INVESTORS_PATH: &str = "/fund/BFI82U";
+const SINGLE_STOCK_INSTITUTIONAL_INVESTORS_PATH: &str = "/fund/T86";
+
+// the OpenAPI returns arrays of named-field objects which are mapped by the
+// serde aliases on the schema types, and only serves the latest period
+fn get_rows<T: DeserializeOwned>(link: &str, path: &str) -> Result<Vec<T>, TwseError> {
+    let rows: Vec<T> = ureq::get(&format!("{}{}", link, path))
+        .set("accept", "application/json")
+        .call()?
+        .into_json()?;
+    if rows.is_empty() {
+        return Err(TwseError::TWSEError(NO_DATA.to_string()));
+    }
+
+    Ok(rows)
+}
+
+pub(crate) fn get_market_daily_summaries(link: &str) -> Result<Vec<MarketDailySummary>, TwseError> {
+    get_rows(link, MARKET_DAILY_SUMMARY_PATH)
+}
+
+pub(crate) fn get_taiex_history(link: &str) -> Result<Vec<TaiexHistory>, TwseError> {
+    get_rows(link, TAIEX_HISTORY_PATH)
+}
+
+pub(crate) fn get_daily_quotes(link: &str) -> Result<Vec<DailyQuote>, TwseError> {
+    get_rows(link, DAILY_QUOTES_PATH)
+}
+
+pub(crate) fn get_foreign_holdings(link: &str) -> Result<Vec<ForeignHolding>, TwseError> {
+    get_rows(link, FOREIGN_HOLDINGS_PATH)
+}
+
+pub(crate) fn get_total_institution_investors(
+    link: &str,
+) -> Result<Vec<TotalInstitutionalInvestors>, TwseError> {
+    get_rows(link, TOTAL_INSTITUTIONAL_INVESTORS_PATH)
+}
+
+pub(crate) fn get_single_stock_institutional_investors(
+    link: &str,
+) -> Result<Vec<SingleStockInstitutionalInvestors>, TwseError> {
+    get_rows(link, SINGLE_STOCK_INSTITUTIONAL_INVESTORS_PATH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::serve;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_get_rows_from_stand_in_server() {
+        let summaries = r#"[
+            {"Date":"1100201","TradeVolume":"7,251,385,462","TradeValue":"305,624,553,487","Transaction":"1,752,329","TAIEX":"15,410.09","Change":"271.78"},
+            {"Date":"1100202","TradeVolume":"6,901,113,044","TradeValue":"283,071,812,914","Transaction":"1,618,032","TAIEX":"15,760.05","Change":"349.96"}
+        ]"#;
+        let history = r#"[
+            {"Date":"1100201","OpeningIndex":"15,221.29","HighestIndex":"15,443.92","LowestIndex":"15,221.29","ClosingIndex":"15,410.09"}
+        ]"#;
+        let link = serve(move |path| match path {
+            "/exchangeReport/FMTQIK" => Some(summaries.to_string()),
+            "/indicesReport/MI_5MINS_HIST" => Some(history.to_string()),
+            "/exchangeReport/EMPTY" => Some("[]".to_string()),
+            _ => None,
+        });
+
+        let summaries = get_market_daily_summaries(&link).unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            summaries[1].date(),
+            NaiveDate::from_ymd_opt(2021, 2, 2).unwrap()
+        );
+
+        let history = get_taiex_history(&link).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            history[0].date(),
+            NaiveDate::from_ymd_opt(2021, 2, 1).unwrap()
+        );
+
+        let got = get_rows::<TaiexHistory>(&link, "/exchangeReport/EMPTY");
+        assert!(got.unwrap_err().is_no_data());
+        let got = get_rows::<TaiexHistory>(&link, "/exchangeReport/UNKNOWN");
+        assert!(matches!(got, Err(TwseError::UreqError(_))));
+    }
+
+    #[test]
+    fn test_get_market_daily_summaries() {
+        assert!(get_market_daily_summaries(OPENAPI_LINK).is_ok());
+    }
+
+    #[test]
+    fn test_get_taiex_history() {
+        assert!(get_taiex_history(OPENAPI_LINK).is_ok());
+    }
+
+    #[test]
+    fn test_get_daily_quotes() {
+        assert!(get_daily_quotes(OPENAPI_LINK).is_ok());
+    }
+
+    #[test]
+    fn test_get_foreign_holdings() {
+        assert!(get_foreign_holdings(OPENAPI_LINK).is_ok());
+    }
+}
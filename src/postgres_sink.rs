@@ -0,0 +1,479 @@
+use crate::args::{
+    DateType, IndustryType, Market, SingleStockInstitutionalInvestorsArgs,
+    TotalInstitutionInvestorsArgs,
+};
+use crate::client::Client;
+use crate::errors::TwseError;
+use crate::schema::{SingleStockInstitutionalInvestors, TotalInstitutionalInvestors};
+use crate::writer::{field_names, CsvWriter, Headers};
+use chrono::NaiveDate;
+use postgres::{NoTls, Transaction};
+use serde::Serialize;
+
+const SINGLE_STOCK_INSTITUTIONAL: &str = "single_stock_institutional";
+const TOTAL_INSTITUTIONAL: &str = "total_institutional";
+
+// applied in order and recorded in twse_migrations, never edit one which is released
+const MIGRATIONS: [(i32, &str, &str); 1] = [(
+    1,
+    "create the institutional investors tables",
+    "
+    CREATE TABLE single_stock_institutional (
+        date DATE NOT NULL,
+        date_type TEXT NOT NULL,
+        stock_id TEXT NOT NULL,
+        stock_name TEXT NOT NULL,
+        foreign_investor_buy BIGINT NOT NULL,
+        foreign_investor_sell BIGINT NOT NULL,
+        foreign_investor_difference BIGINT NOT NULL,
+        foreign_dealer_self_buy BIGINT NOT NULL,
+        foreign_dealer_self_sell BIGINT NOT NULL,
+        foreign_dealer_self_difference BIGINT NOT NULL,
+        investment_trust_buy BIGINT NOT NULL,
+        investment_trust_sell BIGINT NOT NULL,
+        investment_trust_difference BIGINT NOT NULL,
+        dealer_total_difference BIGINT NOT NULL,
+        dealer_self_buy BIGINT NOT NULL,
+        dealer_self_sell BIGINT NOT NULL,
+        dealer_self_difference BIGINT NOT NULL,
+        dealer_hedging_buy BIGINT NOT NULL,
+        dealer_hedging_sell BIGINT NOT NULL,
+        dealer_difference BIGINT NOT NULL,
+        total_difference BIGINT NOT NULL,
+        stock_name_en TEXT,
+        PRIMARY KEY (date, date_type, stock_id)
+    );
+    CREATE TABLE total_institutional (
+        date DATE NOT NULL,
+        date_type TEXT NOT NULL,
+        name TEXT NOT NULL,
+        buy BIGINT NOT NULL,
+        sell BIGINT NOT NULL,
+        difference BIGINT NOT NULL,
+        name_en TEXT,
+        PRIMARY KEY (date, date_type, name)
+    );
+    ",
+)];
+
+// writes the institutional investors of TWSE into postgres, the rows of a report
+// are copied into a staging table and upserted from there, so a day fetched again
+// replaces its rows instead of failing
+pub struct PostgresSink {
+    client: postgres::Client,
+}
+
+impl PostgresSink {
+    // e.g. host=localhost user=postgres dbname=twse
+    pub fn connect(params: &str) -> Result<PostgresSink, TwseError> {
+        PostgresSink::new(postgres::Client::connect(params, NoTls)?)
+    }
+
+    pub fn new(client: postgres::Client) -> Result<PostgresSink, TwseError> {
+        let mut sink = PostgresSink { client };
+        sink.migrate()?;
+        Ok(sink)
+    }
+
+    // applies the migrations which are not in twse_migrations yet
+    pub fn migrate(&mut self) -> Result<(), TwseError> {
+        self.client.batch_execute(
+            "CREATE TABLE IF NOT EXISTS twse_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )",
+        )?;
+
+        let mut tx = self.client.transaction()?;
+        // another job migrating at the same time waits for this one
+        tx.batch_execute("LOCK TABLE twse_migrations IN EXCLUSIVE MODE")?;
+        let applied: i32 = tx
+            .query_one("SELECT coalesce(max(version), 0) FROM twse_migrations", &[])?
+            .get(0);
+        for (version, name, sql) in MIGRATIONS.iter() {
+            if *version <= applied {
+                continue;
+            }
+            tx.batch_execute(sql)?;
+            tx.execute(
+                "INSERT INTO twse_migrations (version, name) VALUES ($1, $2)",
+                &[version, name],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub fn schema_version(&mut self) -> Result<i32, TwseError> {
+        Ok(self
+            .client
+            .query_one("SELECT coalesce(max(version), 0) FROM twse_migrations", &[])?
+            .get(0))
+    }
+
+    pub fn write_single_stock_institutional(
+        &mut self,
+        date: NaiveDate,
+        date_type: DateType,
+        rows: &[SingleStockInstitutionalInvestors],
+    ) -> Result<usize, TwseError> {
+        let mut tx = self.client.transaction()?;
+        let count = upsert(
+            &mut tx,
+            SINGLE_STOCK_INSTITUTIONAL,
+            "stock_id",
+            date,
+            date_type,
+            rows,
+        )?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    pub fn write_total_institutional(
+        &mut self,
+        date: NaiveDate,
+        date_type: DateType,
+        rows: &[TotalInstitutionalInvestors],
+    ) -> Result<usize, TwseError> {
+        let mut tx = self.client.transaction()?;
+        let count = upsert(&mut tx, TOTAL_INSTITUTIONAL, "name", date, date_type, rows)?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    // fetches T86 of all industries and BFI82U of a date and writes them,
+    // a date without reports writes nothing
+    pub fn ingest(
+        &mut self,
+        client: &Client,
+        date: NaiveDate,
+        date_type: DateType,
+    ) -> Result<usize, TwseError> {
+        let single = client.get_single_stock_institutional_investors(
+            SingleStockInstitutionalInvestorsArgs {
+                date,
+                date_type,
+                market: Market::Twse,
+                industry_type: IndustryType::All,
+            },
+        );
+        let total = client.get_total_institution_investors(TotalInstitutionInvestorsArgs {
+            date,
+            date_type,
+            market: Market::Twse,
+        });
+
+        self.write_reports(date, date_type, single, total)
+    }
+
+    // a report without data is skipped, any other error stops before the total is written
+    fn write_reports(
+        &mut self,
+        date: NaiveDate,
+        date_type: DateType,
+        single: Result<Vec<SingleStockInstitutionalInvestors>, TwseError>,
+        total: Result<Vec<TotalInstitutionalInvestors>, TwseError>,
+    ) -> Result<usize, TwseError> {
+        let mut count = 0;
+        match single {
+            Ok(rows) => count += self.write_single_stock_institutional(date, date_type, &rows)?,
+            Err(e) if e.is_no_data() => {}
+            Err(e) => return Err(e),
+        }
+        match total {
+            Ok(rows) => count += self.write_total_institutional(date, date_type, &rows)?,
+            Err(e) if e.is_no_data() => {}
+            Err(e) => return Err(e),
+        }
+
+        Ok(count)
+    }
+}
+
+// the English names are kept when a report is fetched again without them
+fn upsert<R: Serialize>(
+    tx: &mut Transaction,
+    table: &str,
+    key: &str,
+    date: NaiveDate,
+    date_type: DateType,
+    rows: &[R],
+) -> Result<usize, TwseError> {
+    let mut columns = match rows.first() {
+        Some(row) => field_names(row)?,
+        None => return Ok(0),
+    };
+    columns.insert(0, "date".to_string());
+    let column_list = columns.join(", ");
+
+    tx.batch_execute(&format!(
+        "CREATE TEMP TABLE staging AS SELECT * FROM {} WITH NO DATA",
+        table
+    ))?;
+    let mut copy = tx.copy_in(
+        format!(
+            "COPY staging ({}) FROM STDIN WITH (FORMAT csv, HEADER true)",
+            column_list
+        )
+        .as_str(),
+    )?;
+    let mut writer = CsvWriter::new(&mut copy, Headers::FieldNames);
+    writer.write(date, rows)?;
+    writer.flush()?;
+    drop(writer);
+    copy.finish()?;
+
+    // a key listed twice in a report would make the upsert fail, and which of the rows
+    // is right cannot be told
+    let duplicated = tx.query_opt(
+        format!(
+            "SELECT {key} FROM staging GROUP BY {key} HAVING count(*) > 1 ORDER BY {key} LIMIT 1",
+            key = key
+        )
+        .as_str(),
+        &[],
+    )?;
+    if let Some(row) = duplicated {
+        return Err(TwseError::DuplicateKeyError(row.get(0)));
+    }
+
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|column| *column != "date" && *column != key)
+        .map(|column| format!("{0} = coalesce(excluded.{0}, target.{0})", column))
+        .collect();
+    tx.execute(
+        format!(
+            "INSERT INTO {table} AS target (date_type, {columns})
+            SELECT $1, {columns} FROM staging
+            ON CONFLICT (date, date_type, {key}) DO UPDATE SET {updates}",
+            table = table,
+            columns = column_list,
+            key = key,
+            updates = updates.join(", ")
+        )
+        .as_str(),
+        &[&date_type.value()],
+    )?;
+    tx.batch_execute("DROP TABLE staging")?;
+
+    Ok(rows.len())
+}
+
+// the tests need the database of TWSE_POSTGRES_URL and are ignored by default, e.g.
+// TWSE_POSTGRES_URL="host=localhost user=postgres" cargo test --features postgres -- --ignored
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Data, Response};
+    use std::env;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SCHEMAS: AtomicUsize = AtomicUsize::new(0);
+
+    // every test gets its own schema, dropped when it is done
+    struct TestDatabase {
+        params: String,
+        schema: String,
+    }
+
+    impl TestDatabase {
+        fn new() -> TestDatabase {
+            let params = env::var("TWSE_POSTGRES_URL").expect("TWSE_POSTGRES_URL is not set");
+            let schema = format!(
+                "twse_test_{}_{}",
+                std::process::id(),
+                SCHEMAS.fetch_add(1, Ordering::SeqCst)
+            );
+            let mut client = postgres::Client::connect(&params, NoTls).unwrap();
+            client
+                .batch_execute(&format!("CREATE SCHEMA {}", schema))
+                .unwrap();
+            TestDatabase { params, schema }
+        }
+
+        fn client(&self) -> postgres::Client {
+            let mut client = postgres::Client::connect(&self.params, NoTls).unwrap();
+            client
+                .batch_execute(&format!("SET search_path TO {}", self.schema))
+                .unwrap();
+            client
+        }
+    }
+
+    impl Drop for TestDatabase {
+        fn drop(&mut self) {
+            if let Ok(mut client) = postgres::Client::connect(&self.params, NoTls) {
+                let _ = client.batch_execute(&format!("DROP SCHEMA {} CASCADE", self.schema));
+            }
+        }
+    }
+
+    fn rows(response: &str) -> Vec<Data> {
+        let response: Response = serde_json::from_str(response).unwrap();
+        response.data
+    }
+
+    fn total_rows() -> Vec<TotalInstitutionalInvestors> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 三大法人買賣金額統計表",
+            "fields":["單位名稱","買進金額","賣出金額","買賣差額"],
+            "data":[
+                ["自營商(自行買賣)","3,720,692,397","2,657,469,622","1,063,222,775"],
+                ["合計","87,532,849,369","89,714,793,209","-2,181,943,840"]
+            ]
+        }
+        "#;
+        rows(response)
+            .into_iter()
+            .filter_map(|data| match data {
+                Data::TotalInstitutionalInvestors(d) => Some(d),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn single_rows() -> Vec<SingleStockInstitutionalInvestors> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 三大法人買賣超日報",
+            "fields":["證券代號","證券名稱","外陸資買進股數(不含外資自營商)","外陸資賣出股數(不含外資自營商)","外陸資買賣超股數(不含外資自營商)","外資自營商買進股數","外資自營商賣出股數","外資自營商買賣超股數","投信買進股數","投信賣出股數","投信買賣超股數","自營商買賣超股數","自營商買進股數(自行買賣)","自營商賣出股數(自行買賣)","自營商買賣超股數(自行買賣)","自營商買進股數(避險)","自營商賣出股數(避險)","自營商買賣超股數(避險)","三大法人買賣超股數"],
+            "data":[
+                ["0050","元大台灣50      ","1,316,473","1,079,050","237,423","0","0","0","0","0","0","3,000","3,000","0","3,000","0","0","0","240,423"],
+                ["2330","台積電          ","20,325,158","25,909,283","-5,584,125","0","0","0","295,000","161,000","134,000","-581,233","249,000","188,000","61,000","352,767","995,000","-642,233","-6,031,358"]
+            ]
+        }
+        "#;
+        rows(response)
+            .into_iter()
+            .filter_map(|data| match data {
+                Data::SingleStockInstitutionalInvestors(d) => Some(d),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, 2, 5).unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs TWSE_POSTGRES_URL"]
+    fn test_migrate() {
+        let database = TestDatabase::new();
+
+        let mut sink = PostgresSink::new(database.client()).unwrap();
+        assert_eq!(sink.schema_version().unwrap(), MIGRATIONS.len() as i32);
+        // a second job finds the tables in place
+        let mut sink = PostgresSink::new(database.client()).unwrap();
+        assert_eq!(sink.schema_version().unwrap(), MIGRATIONS.len() as i32);
+
+        let mut client = database.client();
+        let applied: i64 = client
+            .query_one("SELECT count(*) FROM twse_migrations", &[])
+            .unwrap()
+            .get(0);
+        assert_eq!(applied, MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    #[ignore = "needs TWSE_POSTGRES_URL"]
+    fn test_write_is_idempotent() {
+        let database = TestDatabase::new();
+        let mut sink = PostgresSink::new(database.client()).unwrap();
+
+        let english = TotalInstitutionalInvestors::with_english_names(total_rows(), total_rows());
+        for _ in 0..2 {
+            sink.write_total_institutional(date(), DateType::Day, &english)
+                .unwrap();
+            sink.write_single_stock_institutional(date(), DateType::Day, &single_rows())
+                .unwrap();
+        }
+        // fetched again without English names
+        assert_eq!(
+            sink.write_total_institutional(date(), DateType::Day, &total_rows())
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            sink.write_total_institutional(date(), DateType::Day, &[])
+                .unwrap(),
+            0
+        );
+        sink.write_total_institutional(date(), DateType::Week, &total_rows())
+            .unwrap();
+
+        let mut client = database.client();
+        let count: i64 = client
+            .query_one("SELECT count(*) FROM total_institutional", &[])
+            .unwrap()
+            .get(0);
+        assert_eq!(count, 4);
+        let row = client
+            .query_one(
+                "SELECT difference, name_en FROM total_institutional WHERE date = $1 AND date_type = 'day' AND name = '合計'",
+                &[&date()],
+            )
+            .unwrap();
+        assert_eq!(row.get::<_, i64>(0), -2181943840);
+        assert_eq!(row.get::<_, Option<String>>(1).as_deref(), Some("合計"));
+
+        let row = client
+            .query_one(
+                "SELECT stock_name, total_difference, stock_name_en FROM single_stock_institutional WHERE stock_id = '2330'",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(row.get::<_, String>(0), "台積電");
+        assert_eq!(row.get::<_, i64>(1), -6031358);
+        assert_eq!(row.get::<_, Option<String>>(2), None);
+    }
+
+    #[test]
+    #[ignore = "needs TWSE_POSTGRES_URL"]
+    fn test_write_refuses_duplicated_keys() {
+        let database = TestDatabase::new();
+        let mut sink = PostgresSink::new(database.client()).unwrap();
+
+        let mut rows = total_rows();
+        rows.extend(total_rows().into_iter().skip(1));
+        let got = sink.write_total_institutional(date(), DateType::Day, &rows);
+        assert!(matches!(got, Err(TwseError::DuplicateKeyError(key)) if key == "合計"));
+
+        let mut client = database.client();
+        let count: i64 = client
+            .query_one("SELECT count(*) FROM total_institutional", &[])
+            .unwrap()
+            .get(0);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    #[ignore = "needs TWSE_POSTGRES_URL"]
+    fn test_write_reports_only_skips_no_data() {
+        let database = TestDatabase::new();
+        let mut sink = PostgresSink::new(database.client()).unwrap();
+
+        let no_data = || TwseError::TWSEError("很抱歉，沒有符合條件的資料!".to_string());
+        let got = sink.write_reports(date(), DateType::Day, Err(no_data()), Ok(total_rows()));
+        assert_eq!(got.unwrap(), 2);
+
+        let got = sink.write_reports(
+            date(),
+            DateType::Day,
+            Err(TwseError::TWSEError(
+                "查詢日期大於今日，請重新查詢!".to_string(),
+            )),
+            Ok(total_rows()),
+        );
+        assert!(matches!(got, Err(TwseError::TWSEError(_))));
+    }
+}
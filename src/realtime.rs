@@ -0,0 +1,475 @@
+use crate::args::Market;
+use crate::cell;
+use crate::errors::TwseError;
+use crate::schema::clean_string;
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::ser::{Error as _, SerializeStruct};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{HashMap, VecDeque};
+use std::thread;
+use std::time::Duration;
+
+const MIS_LINK: &str = "https://mis.twse.com.tw/stock/api/getStockInfo.jsp";
+const MIS_STATE_OK: &str = "0000";
+const MIS_DATETIME_FORMAT: &str = "%Y%m%d %H:%M:%S";
+// the MIS site itself never asks for more symbols in a single ex_ch,
+// larger batches get truncated responses or an over-long url
+pub const MAX_SYMBOLS_PER_REQUEST: usize = 100;
+// MIS gives five levels a side, written as a price and a volume column each
+const BID_COLUMNS: [[&str; 2]; 5] = [
+    ["bid_1_price", "bid_1_volume"],
+    ["bid_2_price", "bid_2_volume"],
+    ["bid_3_price", "bid_3_volume"],
+    ["bid_4_price", "bid_4_volume"],
+    ["bid_5_price", "bid_5_volume"],
+];
+const ASK_COLUMNS: [[&str; 2]; 5] = [
+    ["ask_1_price", "ask_1_volume"],
+    ["ask_2_price", "ask_2_volume"],
+    ["ask_3_price", "ask_3_volume"],
+    ["ask_4_price", "ask_4_volume"],
+    ["ask_5_price", "ask_5_volume"],
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub market: Market,
+    pub stock_id: String,
+}
+
+impl Symbol {
+    pub fn new(market: Market, stock_id: &str) -> Symbol {
+        Symbol {
+            market,
+            stock_id: stock_id.to_string(),
+        }
+    }
+
+    // e.g. tse_2330.tw or otc_6488.tw
+    pub fn ex_ch(&self) -> String {
+        match self.market {
+            Market::Twse => format!("tse_{}.tw", self.stock_id),
+            Market::Tpex => format!("otc_{}.tw", self.stock_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub stock_id: String,
+    pub stock_name: String,
+    pub datetime: NaiveDateTime,
+    // None when there is no trade yet
+    pub last_price: Option<Decimal>,
+    pub last_volume: Option<u64>,
+    pub accumulated_volume: u64,
+    pub open: Option<Decimal>,
+    pub high: Option<Decimal>,
+    pub low: Option<Decimal>,
+    pub previous_close: Decimal,
+    pub limit_up: Decimal,
+    pub limit_down: Decimal,
+    // the five best levels as (price, volume), best first
+    pub best_bids: Vec<(Decimal, u64)>,
+    pub best_asks: Vec<(Decimal, u64)>,
+}
+
+// the levels are written flat, csv has no nested values
+impl Serialize for Quote {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.best_bids.len() > BID_COLUMNS.len() || self.best_asks.len() > ASK_COLUMNS.len() {
+            return Err(S::Error::custom(format!(
+                "more than {} levels of {}",
+                BID_COLUMNS.len(),
+                self.stock_id
+            )));
+        }
+
+        let mut row = serializer
+            .serialize_struct("Quote", 12 + 2 * (BID_COLUMNS.len() + ASK_COLUMNS.len()))?;
+        row.serialize_field("stock_id", &self.stock_id)?;
+        row.serialize_field("stock_name", &self.stock_name)?;
+        row.serialize_field("datetime", &self.datetime)?;
+        row.serialize_field("last_price", &self.last_price)?;
+        row.serialize_field("last_volume", &self.last_volume)?;
+        row.serialize_field("accumulated_volume", &self.accumulated_volume)?;
+        row.serialize_field("open", &self.open)?;
+        row.serialize_field("high", &self.high)?;
+        row.serialize_field("low", &self.low)?;
+        row.serialize_field("previous_close", &self.previous_close)?;
+        row.serialize_field("limit_up", &self.limit_up)?;
+        row.serialize_field("limit_down", &self.limit_down)?;
+        for (columns, levels) in [
+            (BID_COLUMNS, &self.best_bids),
+            (ASK_COLUMNS, &self.best_asks),
+        ] {
+            for (n, [price, volume]) in columns.iter().enumerate() {
+                let level = levels.get(n);
+                row.serialize_field(price, &level.map(|l| l.0))?;
+                row.serialize_field(volume, &level.map(|l| l.1))?;
+            }
+        }
+        row.end()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MisResponse {
+    #[serde(rename = "msgArray", default)]
+    msg_array: Vec<MisQuote>,
+    rtcode: String,
+    #[serde(default)]
+    rtmessage: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MisQuote {
+    #[serde(rename = "c")]
+    stock_id: String,
+    #[serde(rename = "n", deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(rename = "d")]
+    date: String,
+    #[serde(rename = "t")]
+    time: String,
+    #[serde(rename = "z", default)]
+    last_price: String,
+    #[serde(rename = "tv", default)]
+    last_volume: String,
+    #[serde(rename = "v", default)]
+    accumulated_volume: String,
+    #[serde(rename = "o", default)]
+    open: String,
+    #[serde(rename = "h", default)]
+    high: String,
+    #[serde(rename = "l", default)]
+    low: String,
+    #[serde(rename = "y")]
+    previous_close: String,
+    #[serde(rename = "u")]
+    limit_up: String,
+    #[serde(rename = "w")]
+    limit_down: String,
+    #[serde(rename = "b", default)]
+    bid_prices: String,
+    #[serde(rename = "g", default)]
+    bid_volumes: String,
+    #[serde(rename = "a", default)]
+    ask_prices: String,
+    #[serde(rename = "f", default)]
+    ask_volumes: String,
+}
+
+impl MisQuote {
+    fn into_quote(self) -> Result<Quote, TwseError> {
+        let datetime = NaiveDateTime::parse_from_str(
+            &format!("{} {}", self.date, self.time),
+            MIS_DATETIME_FORMAT,
+        )
+        .map_err(|e| TwseError::TWSEError(format!("invalid MIS datetime: {}", e)))?;
+
+        Ok(Quote {
+            datetime,
+            last_price: parse_price(&self.last_price)?,
+            last_volume: parse_volume(&self.last_volume),
+            accumulated_volume: parse_volume(&self.accumulated_volume).unwrap_or(0),
+            open: parse_price(&self.open)?,
+            high: parse_price(&self.high)?,
+            low: parse_price(&self.low)?,
+            previous_close: required_price(&self.previous_close)?,
+            limit_up: required_price(&self.limit_up)?,
+            limit_down: required_price(&self.limit_down)?,
+            best_bids: parse_levels(&self.bid_prices, &self.bid_volumes)?,
+            best_asks: parse_levels(&self.ask_prices, &self.ask_volumes)?,
+            stock_id: self.stock_id,
+            stock_name: self.stock_name,
+        })
+    }
+}
+
+// MIS uses "-" for missing values
+fn parse_price(s: &str) -> Result<Option<Decimal>, TwseError> {
+    match s.trim() {
+        "-" => Ok(None),
+        s => Ok(cell::parse::<Decimal>(s)?.value()),
+    }
+}
+
+fn required_price(s: &str) -> Result<Decimal, TwseError> {
+    parse_price(s)?.ok_or_else(|| TwseError::TWSEError(format!("invalid MIS price {}", s)))
+}
+
+fn parse_volume(s: &str) -> Option<u64> {
+    s.trim().parse::<u64>().ok()
+}
+
+// levels are underscore terminated, e.g. 584.0000_583.0000_ and 100_200_
+fn parse_levels(prices: &str, volumes: &str) -> Result<Vec<(Decimal, u64)>, TwseError> {
+    let mut levels = vec![];
+    for (p, v) in prices.split('_').zip(volumes.split('_')) {
+        if let (Some(price), Some(volume)) = (parse_price(p)?, parse_volume(v)) {
+            levels.push((price, volume));
+        }
+    }
+
+    Ok(levels)
+}
+
+pub struct MisClient {
+    link: String,
+    batch_size: usize,
+    agent: ureq::Agent,
+}
+
+impl Default for MisClient {
+    fn default() -> Self {
+        MisClient::new()
+    }
+}
+
+impl MisClient {
+    pub fn new() -> MisClient {
+        MisClient::with_link(MIS_LINK)
+    }
+
+    // points the client to another getStockInfo.jsp, e.g. a local stand-in server
+    pub fn with_link(link: &str) -> MisClient {
+        MisClient {
+            link: link.to_string(),
+            batch_size: MAX_SYMBOLS_PER_REQUEST,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(10))
+                .build(),
+        }
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> MisClient {
+        self.batch_size = batch_size.clamp(1, MAX_SYMBOLS_PER_REQUEST);
+        self
+    }
+
+    pub fn get_quotes(&self, symbols: &[Symbol]) -> Result<Vec<Quote>, TwseError> {
+        let mut ret: Vec<Quote> = Vec::with_capacity(symbols.len());
+        for batch in symbols.chunks(self.batch_size) {
+            let ex_ch = batch
+                .iter()
+                .map(|s| s.ex_ch())
+                .collect::<Vec<String>>()
+                .join("|");
+
+            let response: MisResponse = self
+                .agent
+                .get(&self.link)
+                .query("ex_ch", &ex_ch)
+                .query("json", "1")
+                .query("delay", "0")
+                .call()?
+                .into_json()?;
+            if response.rtcode != MIS_STATE_OK {
+                return Err(TwseError::TWSEError(response.rtmessage));
+            }
+
+            for quote in response.msg_array {
+                ret.push(quote.into_quote()?);
+            }
+        }
+
+        Ok(ret)
+    }
+
+    // polls every interval and only yields the quotes which changed since the last poll
+    pub fn poll(&self, symbols: Vec<Symbol>, interval: Duration) -> QuoteStream<'_> {
+        QuoteStream {
+            client: self,
+            symbols,
+            interval,
+            last: HashMap::new(),
+            pending: VecDeque::new(),
+            polled: false,
+        }
+    }
+}
+
+pub struct QuoteStream<'a> {
+    client: &'a MisClient,
+    symbols: Vec<Symbol>,
+    interval: Duration,
+    last: HashMap<String, Quote>,
+    pending: VecDeque<Quote>,
+    polled: bool,
+}
+
+impl Iterator for QuoteStream<'_> {
+    type Item = Result<Quote, TwseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            if self.polled {
+                thread::sleep(self.interval);
+            }
+            self.polled = true;
+
+            let quotes = match self.client.get_quotes(&self.symbols) {
+                Ok(quotes) => quotes,
+                Err(e) => return Some(Err(e)),
+            };
+            for quote in quotes {
+                if self.last.get(&quote.stock_id) != Some(&quote) {
+                    self.last.insert(quote.stock_id.clone(), quote.clone());
+                    self.pending.push_back(quote);
+                }
+            }
+        }
+
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::serve;
+    use rust_decimal_macros::dec;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // serves getStockInfo.jsp on localhost, 2330 goes up a tick every second request
+    fn stand_in_server(requests: Arc<AtomicUsize>) -> String {
+        let link = serve(move |path| {
+            let n = requests.fetch_add(1, Ordering::SeqCst);
+            let ex_ch = path
+                .split(['?', '&'])
+                .find_map(|q| q.strip_prefix("ex_ch="))
+                .unwrap_or("")
+                .replace("%7C", "|")
+                .replace("%7c", "|");
+            let quotes = ex_ch
+                .split('|')
+                .map(|symbol| {
+                    let stock_id = symbol
+                        .trim_start_matches("tse_")
+                        .trim_start_matches("otc_")
+                        .trim_end_matches(".tw");
+                    let price = if stock_id == "2330" { 585 + n / 2 } else { 135 };
+                    format!(
+                        r#"{{"c":"{}","n":"name","d":"20210205","t":"09:00:0{}","z":"{}.0000","tv":"1","v":"{}","o":"-","h":"-","l":"-","y":"585.0000","u":"643.0000","w":"527.0000","b":"{}.0000_{}.0000_","g":"10_20_","a":"-","f":"-"}}"#,
+                        stock_id,
+                        n / 2,
+                        price,
+                        100 + n / 2,
+                        price - 1,
+                        price - 2
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(",");
+
+            Some(format!(
+                r#"{{"msgArray":[{}],"rtcode":"0000","rtmessage":"OK"}}"#,
+                quotes
+            ))
+        });
+
+        format!("{}/stock/api/getStockInfo.jsp", link)
+    }
+
+    #[test]
+    fn test_parse_mis_quote() {
+        let response = r#"
+        {
+            "msgArray":[{
+                "tv":"3","ps":"3","pz":"585.0000","a":"586.0000_587.0000_588.0000_589.0000_590.0000_",
+                "b":"585.0000_584.0000_583.0000_582.0000_581.0000_","c":"2330","d":"20210205",
+                "ch":"2330.tw","tlong":"1612488601000","f":"326_634_404_523_631_","ip":"0",
+                "g":"55_384_371_318_235_","mt":"000000","h":"598.0000","i":"24","it":"12",
+                "l":"584.0000","n":"台積電","o":"597.0000","p":"0","ex":"tse","s":"3",
+                "t":"09:30:01","u":"652.0000","v":"14589","w":"534.0000","nf":"台灣積體電路製造股份有限公司",
+                "y":"593.0000","z":"585.0000","ts":"0"
+            }],
+            "referer":"","userDelay":0,"rtcode":"0000","queryTime":{},"rtmessage":"OK"
+        }
+        "#;
+        let response: MisResponse = serde_json::from_str(response).unwrap();
+        assert_eq!(response.rtcode, MIS_STATE_OK);
+
+        let quote = response
+            .msg_array
+            .into_iter()
+            .next()
+            .unwrap()
+            .into_quote()
+            .unwrap();
+        assert_eq!(quote.stock_id, "2330");
+        assert_eq!(quote.stock_name, "台積電");
+        assert_eq!(
+            quote.datetime,
+            NaiveDateTime::parse_from_str("20210205 09:30:01", MIS_DATETIME_FORMAT).unwrap()
+        );
+        assert_eq!(quote.last_price, Some(dec!(585)));
+        assert_eq!(quote.last_volume, Some(3));
+        assert_eq!(quote.accumulated_volume, 14589);
+        assert_eq!(quote.open, Some(dec!(597)));
+        assert_eq!(quote.previous_close, dec!(593));
+        assert_eq!(quote.limit_up, dec!(652));
+        assert_eq!(quote.limit_down, dec!(534));
+        assert_eq!(quote.best_bids.len(), 5);
+        assert_eq!(quote.best_bids[0], (dec!(585), 55));
+        assert_eq!(quote.best_asks[4], (dec!(590), 631));
+
+        let json = serde_json::to_value(&quote).unwrap();
+        assert_eq!(json["stock_id"], "2330");
+        assert_eq!(json["bid_1_volume"], 55);
+    }
+
+    #[test]
+    fn test_symbol_ex_ch() {
+        assert_eq!(Symbol::new(Market::Twse, "2330").ex_ch(), "tse_2330.tw");
+        assert_eq!(Symbol::new(Market::Tpex, "6488").ex_ch(), "otc_6488.tw");
+    }
+
+    #[test]
+    fn test_get_quotes_in_batches() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let client = MisClient::with_link(&stand_in_server(requests.clone())).batch_size(2);
+
+        let symbols = vec![
+            Symbol::new(Market::Twse, "2330"),
+            Symbol::new(Market::Twse, "0050"),
+            Symbol::new(Market::Tpex, "6488"),
+        ];
+        let quotes = client.get_quotes(&symbols).unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        let ids: Vec<&str> = quotes.iter().map(|q| q.stock_id.as_str()).collect();
+        assert_eq!(ids, vec!["2330", "0050", "6488"]);
+        assert_eq!(quotes[0].last_price, Some(dec!(585)));
+        assert_eq!(quotes[0].open, None);
+        assert!(quotes[0].best_asks.is_empty());
+        assert_eq!(quotes[0].best_bids, vec![(dec!(584), 10), (dec!(583), 20)]);
+    }
+
+    #[test]
+    fn test_poll_only_yields_changed_quotes() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let client = MisClient::with_link(&stand_in_server(requests.clone()));
+
+        let symbols = vec![
+            Symbol::new(Market::Twse, "2330"),
+            Symbol::new(Market::Twse, "0050"),
+        ];
+        let quotes: Vec<Quote> = client
+            .poll(symbols, Duration::from_millis(0))
+            .take(3)
+            .collect::<Result<Vec<Quote>, TwseError>>()
+            .unwrap();
+
+        // the 2nd poll has nothing changed, the 3rd one only changes 2330
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(quotes[0].stock_id, "2330");
+        assert_eq!(quotes[0].last_price, Some(dec!(585)));
+        assert_eq!(quotes[1].stock_id, "0050");
+        assert_eq!(quotes[2].stock_id, "2330");
+        assert_eq!(quotes[2].last_price, Some(dec!(586)));
+    }
+}
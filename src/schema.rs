@@ -1,5 +1,15 @@
-use chrono::NaiveDate;
-use serde::{Deserialize, Deserializer};
+use crate::args::{Index, Language};
+use crate::cell::{self, parse_cell, Cell, CellValue};
+use crate::corporate_events::CorporateEvent;
+use crate::errors::TwseError;
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use rust_decimal::Decimal;
+use serde::de::IgnoredAny;
+use serde::ser::{Error as _, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+#[cfg(feature = "nfkc")]
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -12,6 +22,113 @@ pub struct Response {
     pub data: Vec<Data>,
 }
 
+impl Response {
+    // tells which language the fields are in, or fails when they are not the expected columns
+    pub fn language(&self, headers: &[Header]) -> Result<Language, TwseError> {
+        headers_language(&self.fields, headers)
+            .ok_or_else(|| TwseError::FieldsError(self.fields.join(",")))
+    }
+}
+
+pub(crate) fn headers_language(fields: &[String], headers: &[Header]) -> Option<Language> {
+    let matches = |name: fn(&Header) -> &'static str| {
+        fields.len() == headers.len()
+            && fields
+                .iter()
+                .zip(headers)
+                .all(|(field, header)| same_header(field, name(header)))
+    };
+
+    if matches(|header| header.zh) {
+        Some(Language::Chinese)
+    } else if matches(|header| header.en) {
+        Some(Language::English)
+    } else {
+        None
+    }
+}
+
+// a column name of the lang=zh and lang=en responses
+#[derive(Debug, Copy, Clone)]
+pub struct Header {
+    pub zh: &'static str,
+    pub en: &'static str,
+}
+
+const fn header(zh: &'static str, en: &'static str) -> Header {
+    Header { zh, en }
+}
+
+// BFI82U
+pub const TOTAL_INSTITUTIONAL_INVESTORS_HEADERS: [Header; 4] = [
+    header("單位名稱", "Item"),
+    header("買進金額", "Total Buy"),
+    header("賣出金額", "Total Sell"),
+    header("買賣差額", "Difference"),
+];
+
+// T86, TWT54U and TWT47U
+pub const SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS: [Header; 19] = [
+    header("證券代號", "Security Code"),
+    header("證券名稱", "Name"),
+    header(
+        "外陸資買進股數(不含外資自營商)",
+        "Foreign Investors include Mainland Area Investors (Foreign Dealers excluded) Total Buy",
+    ),
+    header(
+        "外陸資賣出股數(不含外資自營商)",
+        "Foreign Investors include Mainland Area Investors (Foreign Dealers excluded) Total Sell",
+    ),
+    header(
+        "外陸資買賣超股數(不含外資自營商)",
+        "Foreign Investors include Mainland Area Investors (Foreign Dealers excluded) Difference",
+    ),
+    header("外資自營商買進股數", "Foreign Dealers Total Buy"),
+    header("外資自營商賣出股數", "Foreign Dealers Total Sell"),
+    header("外資自營商買賣超股數", "Foreign Dealers Difference"),
+    header(
+        "投信買進股數",
+        "Securities Investment Trust Companies Total Buy",
+    ),
+    header(
+        "投信賣出股數",
+        "Securities Investment Trust Companies Total Sell",
+    ),
+    header(
+        "投信買賣超股數",
+        "Securities Investment Trust Companies Difference",
+    ),
+    header("自營商買賣超股數", "Dealers Difference"),
+    header(
+        "自營商買進股數(自行買賣)",
+        "Dealers (Proprietary) Total Buy",
+    ),
+    header(
+        "自營商賣出股數(自行買賣)",
+        "Dealers (Proprietary) Total Sell",
+    ),
+    header(
+        "自營商買賣超股數(自行買賣)",
+        "Dealers (Proprietary) Difference",
+    ),
+    header("自營商買進股數(避險)", "Dealers (Hedge) Total Buy"),
+    header("自營商賣出股數(避險)", "Dealers (Hedge) Total Sell"),
+    header("自營商買賣超股數(避險)", "Dealers (Hedge) Difference"),
+    header("三大法人買賣超股數", "Total Difference"),
+];
+
+// compares without whitespaces, punctuations and cases, TWSE is not consistent with them
+fn same_header(field: &str, name: &str) -> bool {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+
+    normalize(field) == normalize(name)
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "snake_case", untagged)]
 pub enum Data {
@@ -19,22 +136,144 @@ pub enum Data {
     SingleStockInstitutionalInvestors(SingleStockInstitutionalInvestors),
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub struct TotalInstitutionalInvestors {
-    #[serde(deserialize_with = "clean_string")]
+    // the aliases are the OpenAPI field names
+    #[serde(alias = "Item", deserialize_with = "clean_string")]
     name: String,
-    #[serde(deserialize_with = "parse_u64")]
+    #[serde(alias = "TotalBuy", deserialize_with = "parse_u64")]
     buy: u64,
-    #[serde(deserialize_with = "parse_u64")]
+    #[serde(alias = "TotalSell", deserialize_with = "parse_u64")]
     sell: u64,
-    #[serde(deserialize_with = "parse_i64")]
+    #[serde(alias = "Difference", deserialize_with = "parse_i64")]
     difference: i64,
+    // only filled when fetched in both languages
+    #[serde(skip_deserializing)]
+    name_en: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+impl TotalInstitutionalInvestors {
+    // both languages list the same rows in the same order
+    pub fn with_english_names(
+        mut rows: Vec<TotalInstitutionalInvestors>,
+        english: Vec<TotalInstitutionalInvestors>,
+    ) -> Vec<TotalInstitutionalInvestors> {
+        if rows.len() == english.len() {
+            for (row, en) in rows.iter_mut().zip(english) {
+                row.name_en = Some(en.name);
+            }
+        }
+
+        rows
+    }
+}
+
+#[cfg(feature = "arrow")]
+crate::columnar::arrow_row!(
+    TotalInstitutionalInvestors,
+    "total_institutional_investors",
+    [name, buy, sell, difference, name_en]
+);
+
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub struct SingleStockInstitutionalInvestors {
+    // the aliases are the OpenAPI field names
+    #[serde(alias = "Code", deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(alias = "Name", deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(alias = "ForeignInvestorsBuy", deserialize_with = "parse_u64")]
+    foreign_investor_buy: u64,
+    #[serde(alias = "ForeignInvestorsSell", deserialize_with = "parse_u64")]
+    foreign_investor_sell: u64,
+    #[serde(alias = "ForeignInvestorsDifference", deserialize_with = "parse_i64")]
+    foreign_investor_difference: i64,
+    #[serde(alias = "ForeignDealersBuy", deserialize_with = "parse_u64")]
+    foreign_dealer_self_buy: u64,
+    #[serde(alias = "ForeignDealersSell", deserialize_with = "parse_u64")]
+    foreign_dealer_self_sell: u64,
+    #[serde(alias = "ForeignDealersDifference", deserialize_with = "parse_i64")]
+    foreign_dealer_self_difference: i64,
+    #[serde(alias = "InvestmentTrustBuy", deserialize_with = "parse_u64")]
+    investment_trust_buy: u64,
+    #[serde(alias = "InvestmentTrustSell", deserialize_with = "parse_u64")]
+    investment_trust_sell: u64,
+    #[serde(alias = "InvestmentTrustDifference", deserialize_with = "parse_i64")]
+    investment_trust_difference: i64,
+    #[serde(alias = "DealersDifference", deserialize_with = "parse_i64")]
+    dealer_total_difference: i64,
+    #[serde(alias = "DealersProprietaryBuy", deserialize_with = "parse_u64")]
+    dealer_self_buy: u64,
+    #[serde(alias = "DealersProprietarySell", deserialize_with = "parse_u64")]
+    dealer_self_sell: u64,
+    #[serde(alias = "DealersProprietaryDifference", deserialize_with = "parse_i64")]
+    dealer_self_difference: i64,
+    #[serde(alias = "DealersHedgeBuy", deserialize_with = "parse_u64")]
+    dealer_hedging_buy: u64,
+    #[serde(alias = "DealersHedgeSell", deserialize_with = "parse_u64")]
+    dealer_hedging_sell: u64,
+    #[serde(alias = "DealersHedgeDifference", deserialize_with = "parse_i64")]
+    dealer_difference: i64,
+    #[serde(alias = "TotalDifference", deserialize_with = "parse_i64")]
+    total_difference: i64,
+    // only filled when fetched in both languages
+    #[serde(skip_deserializing)]
+    stock_name_en: Option<String>,
+}
+
+impl SingleStockInstitutionalInvestors {
+    pub fn with_english_names(
+        mut rows: Vec<SingleStockInstitutionalInvestors>,
+        english: Vec<SingleStockInstitutionalInvestors>,
+    ) -> Vec<SingleStockInstitutionalInvestors> {
+        let names: HashMap<String, String> = english
+            .into_iter()
+            .map(|en| (en.stock_id, en.stock_name))
+            .collect();
+        for row in rows.iter_mut() {
+            row.stock_name_en = names.get(&row.stock_id).cloned();
+        }
+
+        rows
+    }
+}
+
+#[cfg(feature = "arrow")]
+crate::columnar::arrow_row!(
+    SingleStockInstitutionalInvestors,
+    "single_stock_institutional_investors",
+    [
+        stock_id,
+        stock_name,
+        foreign_investor_buy,
+        foreign_investor_sell,
+        foreign_investor_difference,
+        foreign_dealer_self_buy,
+        foreign_dealer_self_sell,
+        foreign_dealer_self_difference,
+        investment_trust_buy,
+        investment_trust_sell,
+        investment_trust_difference,
+        dealer_total_difference,
+        dealer_self_buy,
+        dealer_self_sell,
+        dealer_self_difference,
+        dealer_hedging_buy,
+        dealer_hedging_sell,
+        dealer_difference,
+        total_difference,
+        stock_name_en,
+    ]
+);
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ForeignInvestorsRanking {
+    // the first column is an always empty checkbox column on the TWSE page
+    #[serde(serialize_with = "empty_cell")]
+    blank: IgnoredAny,
     #[serde(deserialize_with = "clean_string")]
     stock_id: String,
     #[serde(deserialize_with = "clean_string")]
@@ -52,13 +291,106 @@ pub struct SingleStockInstitutionalInvestors {
     #[serde(deserialize_with = "parse_i64")]
     foreign_dealer_self_difference: i64,
     #[serde(deserialize_with = "parse_u64")]
-    investment_trust_buy: u64,
+    total_buy: u64,
     #[serde(deserialize_with = "parse_u64")]
-    investment_trust_sell: u64,
+    total_sell: u64,
     #[serde(deserialize_with = "parse_i64")]
-    investment_trust_difference: i64,
+    total_difference: i64,
+}
+
+impl ForeignInvestorsRanking {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn foreign_investor_buy(&self) -> u64 {
+        self.foreign_investor_buy
+    }
+
+    pub fn foreign_investor_sell(&self) -> u64 {
+        self.foreign_investor_sell
+    }
+
+    pub fn foreign_investor_difference(&self) -> i64 {
+        self.foreign_investor_difference
+    }
+
+    pub fn foreign_dealer_self_buy(&self) -> u64 {
+        self.foreign_dealer_self_buy
+    }
+
+    pub fn foreign_dealer_self_sell(&self) -> u64 {
+        self.foreign_dealer_self_sell
+    }
+
+    pub fn foreign_dealer_self_difference(&self) -> i64 {
+        self.foreign_dealer_self_difference
+    }
+
+    pub fn total_buy(&self) -> u64 {
+        self.total_buy
+    }
+
+    pub fn total_sell(&self) -> u64 {
+        self.total_sell
+    }
+
+    pub fn total_difference(&self) -> i64 {
+        self.total_difference
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct InvestmentTrustRanking {
+    // the first column is an always empty checkbox column on the TWSE page
+    #[serde(serialize_with = "empty_cell")]
+    blank: IgnoredAny,
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_u64")]
+    buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sell: u64,
     #[serde(deserialize_with = "parse_i64")]
-    dealer_total_difference: i64,
+    difference: i64,
+}
+
+impl InvestmentTrustRanking {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn buy(&self) -> u64 {
+        self.buy
+    }
+
+    pub fn sell(&self) -> u64 {
+        self.sell
+    }
+
+    pub fn difference(&self) -> i64 {
+        self.difference
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DealersRanking {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
     #[serde(deserialize_with = "parse_u64")]
     dealer_self_buy: u64,
     #[serde(deserialize_with = "parse_u64")]
@@ -70,332 +402,3502 @@ pub struct SingleStockInstitutionalInvestors {
     #[serde(deserialize_with = "parse_u64")]
     dealer_hedging_sell: u64,
     #[serde(deserialize_with = "parse_i64")]
-    dealer_difference: i64,
+    dealer_hedging_difference: i64,
+    #[serde(deserialize_with = "parse_u64")]
+    total_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    total_sell: u64,
     #[serde(deserialize_with = "parse_i64")]
     total_difference: i64,
 }
 
-const TWSE_DATETIME_FORMAT: &'static str = "%Y%m%d";
+impl DealersRanking {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
 
-fn parse_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    NaiveDate::parse_from_str(&s, TWSE_DATETIME_FORMAT).map_err(serde::de::Error::custom)
-}
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
 
-fn clean_string<'de, D>(deserializer: D) -> Result<String, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    Ok(s.replace(" ", ""))
-    // NaiveDate::parse_from_str(&s, TWSE_DATETIME_FORMAT).map_err(serde::de::Error::custom)
-}
+    pub fn dealer_self_buy(&self) -> u64 {
+        self.dealer_self_buy
+    }
 
-fn parse_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    s.replace(",", "")
-        .parse::<u64>()
-        .map_err(serde::de::Error::custom)
-}
+    pub fn dealer_self_sell(&self) -> u64 {
+        self.dealer_self_sell
+    }
 
-fn parse_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    s.replace(",", "")
-        .parse::<i64>()
-        .map_err(serde::de::Error::custom)
-}
+    pub fn dealer_self_difference(&self) -> i64 {
+        self.dealer_self_difference
+    }
 
-#[cfg(test)]
-use anyhow::{Context, Result};
-#[cfg(test)]
-use serde_json;
-#[cfg(test)]
-mod tests {
-    use super::*;
+    pub fn dealer_hedging_buy(&self) -> u64 {
+        self.dealer_hedging_buy
+    }
 
-    #[test]
-    fn test_response_with_total_institutional_investors() -> Result<()> {
-        let response = r#"
-        {
-            "stat":"OK",
-            "title":"110年02月05日 三大法人買賣金額統計表",
-            "fields":["單位名稱","買進金額","賣出金額","買賣差額"],
-            "date":"20210205",
-            "data":[
-                ["自營商(自行買賣)","3,720,692,397","2,657,469,622","1,063,222,775"],
-                ["自營商(避險)","7,930,149,338","6,161,180,878","1,768,968,460"],
-                ["投信","2,305,132,480","1,693,938,980","611,193,500"],
-                ["外資及陸資","73,576,875,154","79,202,203,729","-5,625,328,575"],
-                ["合計","87,532,849,369","89,714,793,209","-2,181,943,840"]
-            ],
-            "params":{"controller":"fund","format":null,"action":"BFI82U","lang":"zh","monthDate":"20210205","weekDate":"20210201","dayDate":"20210205"},
-            "notes":[
-                "自營商表示證券自營商專戶。",
-                "投信表示本國投資信託基金。",
-                "外資及陸資表示依「華僑及外國人投資證券管理辦法」及「大陸地區投資人來臺從事證券投資及期貨交易管理辦法」辦理登記等投資人。",
-                "本統計資訊含一般、零股、盤後定價、鉅額，不含拍賣、標購。",
-                "本資訊以當日原始成交情形統計，不以證券商申報錯帳、更正帳號等調整後資料統計。"
-            ]
-        }
-        "#;
-        let result: Response = serde_json::from_str(response)
-            .with_context(|| format!("serde_json::from_str failed"))?;
+    pub fn dealer_hedging_sell(&self) -> u64 {
+        self.dealer_hedging_sell
+    }
 
-        assert_eq!(result.stat, "OK");
-        assert_eq!(result.data.len(), 5);
+    pub fn dealer_hedging_difference(&self) -> i64 {
+        self.dealer_hedging_difference
+    }
 
-        let wants = [
-            TotalInstitutionalInvestors {
-                name: "自營商(自行買賣)".to_string(),
-                buy: 3720692397,
-                sell: 2657469622,
-                difference: 1063222775,
-            },
-            TotalInstitutionalInvestors {
-                name: "自營商(避險)".to_string(),
-                buy: 7930149338,
-                sell: 6161180878,
-                difference: 1768968460,
-            },
-            TotalInstitutionalInvestors {
-                name: "投信".to_string(),
-                buy: 2305132480,
-                sell: 1693938980,
-                difference: 611193500,
-            },
-            TotalInstitutionalInvestors {
-                name: "外資及陸資".to_string(),
-                buy: 73576875154,
-                sell: 79202203729,
-                difference: -5625328575,
-            },
-            TotalInstitutionalInvestors {
-                name: "合計".to_string(),
-                buy: 87532849369,
-                sell: 89714793209,
-                difference: -2181943840,
-            },
-        ];
+    pub fn total_buy(&self) -> u64 {
+        self.total_buy
+    }
 
-        for n in 0..result.data.len() {
-            if let Data::TotalInstitutionalInvestors(d) = &result.data[n] {
-                assert_eq!(d.buy, wants[n].buy);
-                assert_eq!(d.sell, wants[n].sell);
-                assert_eq!(d.difference, wants[n].difference);
-                assert_eq!(d.name, wants[n].name);
-            } else {
-                assert!(false, "data vector index {} cannot cast out", n);
-            }
-        }
+    pub fn total_sell(&self) -> u64 {
+        self.total_sell
+    }
 
-        Ok(())
+    pub fn total_difference(&self) -> i64 {
+        self.total_difference
     }
+}
 
-    #[test]
-    fn test_response_with_single_stock_institutional_investors() -> Result<()> {
-        let response = r#"
-        {
-            "stat":"OK",
-            "date":"20210205",
-            "title":"110年02月05日 三大法人買賣超日報",
-            "fields":[
-                "證券代號",
-                "證券名稱",
-                "外陸資買進股數(不含外資自營商)",
-                "外陸資賣出股數(不含外資自營商)",
-                "外陸資買賣超股數(不含外資自營商)",
-                "外資自營商買進股數",
-                "外資自營商賣出股數",
-                "外資自營商買賣超股數",
-                "投信買進股數",
-                "投信賣出股數",
-                "投信買賣超股數",
-                "自營商買賣超股數",
-                "自營商買進股數(自行買賣)",
-                "自營商賣出股數(自行買賣)",
-                "自營商買賣超股數(自行買賣)",
-                "自營商買進股數(避險)",
-                "自營商賣出股數(避險)",
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ShortSaleBalances {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_u64")]
+    margin_short_previous_balance: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    margin_short_sell: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    margin_short_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    margin_short_redemption: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    margin_short_balance: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    margin_short_quota: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sbl_short_previous_balance: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sbl_short_sell: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sbl_short_return: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    sbl_short_adjustment: i64,
+    #[serde(deserialize_with = "parse_u64")]
+    sbl_short_balance: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sbl_short_quota: u64,
+    #[serde(deserialize_with = "clean_string")]
+    note: String,
+}
+
+impl ShortSaleBalances {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn margin_short_previous_balance(&self) -> u64 {
+        self.margin_short_previous_balance
+    }
+
+    pub fn margin_short_sell(&self) -> u64 {
+        self.margin_short_sell
+    }
+
+    pub fn margin_short_buy(&self) -> u64 {
+        self.margin_short_buy
+    }
+
+    pub fn margin_short_redemption(&self) -> u64 {
+        self.margin_short_redemption
+    }
+
+    pub fn margin_short_balance(&self) -> u64 {
+        self.margin_short_balance
+    }
+
+    pub fn margin_short_quota(&self) -> u64 {
+        self.margin_short_quota
+    }
+
+    pub fn sbl_short_previous_balance(&self) -> u64 {
+        self.sbl_short_previous_balance
+    }
+
+    pub fn sbl_short_sell(&self) -> u64 {
+        self.sbl_short_sell
+    }
+
+    pub fn sbl_short_return(&self) -> u64 {
+        self.sbl_short_return
+    }
+
+    pub fn sbl_short_adjustment(&self) -> i64 {
+        self.sbl_short_adjustment
+    }
+
+    pub fn sbl_short_balance(&self) -> u64 {
+        self.sbl_short_balance
+    }
+
+    pub fn sbl_short_quota(&self) -> u64 {
+        self.sbl_short_quota
+    }
+
+    pub fn note(&self) -> &str {
+        &self.note
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SecuritiesLendingTransaction {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_securities_lending_type")]
+    transaction_type: SecuritiesLendingType,
+    #[serde(deserialize_with = "parse_u64")]
+    volume: u64,
+    #[serde(deserialize_with = "parse_decimal")]
+    fee_rate: Decimal,
+    #[serde(deserialize_with = "parse_u64")]
+    duration_days: u64,
+}
+
+impl SecuritiesLendingTransaction {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn transaction_type(&self) -> SecuritiesLendingType {
+        self.transaction_type
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.volume
+    }
+
+    pub fn fee_rate(&self) -> Decimal {
+        self.fee_rate
+    }
+
+    pub fn duration_days(&self) -> u64 {
+        self.duration_days
+    }
+}
+
+// 交易方式
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum SecuritiesLendingType {
+    Competitive,
+    FixedRate,
+    Negotiated,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct BlockTrade {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_block_trade_type")]
+    trade_type: BlockTradeType,
+    #[serde(deserialize_with = "parse_decimal")]
+    price: Decimal,
+    #[serde(deserialize_with = "parse_u64")]
+    volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    value: u64,
+}
+
+impl BlockTrade {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn trade_type(&self) -> BlockTradeType {
+        self.trade_type
+    }
+
+    pub fn price(&self) -> Decimal {
+        self.price
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.volume
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+// 交易別
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum BlockTradeType {
+    Paired,
+    Continuous,
+}
+
+// a report of a single table decoded straight into the rows of its endpoint, as
+// rows of different reports can have the same columns, e.g. the after hours trading
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ReportResponse<T> {
+    pub stat: String,
+    #[serde(deserialize_with = "parse_date")]
+    pub date: NaiveDate,
+    pub title: String,
+    pub fields: Vec<String>,
+    pub data: Vec<T>,
+}
+
+// 盤後定價交易
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FixedPriceTrading {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_u64")]
+    volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    transactions: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    value: u64,
+    // -- when there is no trade
+    #[serde(deserialize_with = "parse_cell")]
+    price: Cell<Decimal>,
+    #[serde(deserialize_with = "parse_u64")]
+    last_best_bid_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    last_best_ask_volume: u64,
+}
+
+impl FixedPriceTrading {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.volume
+    }
+
+    pub fn transactions(&self) -> u64 {
+        self.transactions
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn price(&self) -> Option<Decimal> {
+        self.price.value()
+    }
+
+    pub fn last_best_bid_volume(&self) -> u64 {
+        self.last_best_bid_volume
+    }
+
+    pub fn last_best_ask_volume(&self) -> u64 {
+        self.last_best_ask_volume
+    }
+}
+
+// 盤後零股交易
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct OddLotTrading {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_u64")]
+    volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    transactions: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    value: u64,
+    // -- when there is no trade or no order left
+    #[serde(deserialize_with = "parse_cell")]
+    price: Cell<Decimal>,
+    #[serde(deserialize_with = "parse_cell")]
+    last_best_bid_price: Cell<Decimal>,
+    #[serde(deserialize_with = "parse_cell")]
+    last_best_ask_price: Cell<Decimal>,
+}
+
+impl OddLotTrading {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.volume
+    }
+
+    pub fn transactions(&self) -> u64 {
+        self.transactions
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn price(&self) -> Option<Decimal> {
+        self.price.value()
+    }
+
+    pub fn last_best_bid_price(&self) -> Option<Decimal> {
+        self.last_best_bid_price.value()
+    }
+
+    pub fn last_best_ask_price(&self) -> Option<Decimal> {
+        self.last_best_ask_price.value()
+    }
+}
+
+// 市場成交資訊
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct MarketDailySummary {
+    // the aliases are the OpenAPI field names
+    #[serde(alias = "Date", deserialize_with = "parse_roc_date")]
+    date: NaiveDate,
+    #[serde(alias = "TradeVolume", deserialize_with = "parse_u64")]
+    volume: u64,
+    #[serde(alias = "TradeValue", deserialize_with = "parse_u64")]
+    value: u64,
+    #[serde(alias = "Transaction", deserialize_with = "parse_u64")]
+    transactions: u64,
+    #[serde(alias = "TAIEX", deserialize_with = "parse_decimal")]
+    taiex: Decimal,
+    #[serde(alias = "Change", deserialize_with = "parse_decimal")]
+    change: Decimal,
+}
+
+impl MarketDailySummary {
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+}
+
+#[cfg(feature = "arrow")]
+crate::columnar::arrow_row!(
+    MarketDailySummary,
+    "market_daily_summary",
+    [date, volume, value, transactions, taiex, change]
+);
+
+// 每5秒委託成交統計
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct IntradayMarketStatistics {
+    #[serde(deserialize_with = "parse_time")]
+    time: NaiveTime,
+    #[serde(deserialize_with = "parse_u64")]
+    accumulated_buy_orders: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    accumulated_buy_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    accumulated_sell_orders: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    accumulated_sell_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    accumulated_transactions: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    accumulated_trade_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    accumulated_trade_value: u64,
+}
+
+// a market daily summary joined with the 三大法人買賣金額統計表 of the same day
+#[derive(Debug)]
+pub struct DailyMarketSnapshot {
+    pub date: NaiveDate,
+    pub summary: MarketDailySummary,
+    pub institutional_investors: Option<Vec<TotalInstitutionalInvestors>>,
+}
+
+impl DailyMarketSnapshot {
+    pub fn join(
+        summaries: Vec<MarketDailySummary>,
+        institutional_investors: Vec<(NaiveDate, Vec<TotalInstitutionalInvestors>)>,
+    ) -> Vec<DailyMarketSnapshot> {
+        let mut investors: HashMap<NaiveDate, Vec<TotalInstitutionalInvestors>> =
+            institutional_investors.into_iter().collect();
+
+        summaries
+            .into_iter()
+            .map(|summary| DailyMarketSnapshot {
+                date: summary.date,
+                institutional_investors: investors.remove(&summary.date),
+                summary,
+            })
+            .collect()
+    }
+}
+
+// e.g. institutional_investors_1_name to institutional_investors_1_name_en
+macro_rules! snapshot_columns {
+    ($n:literal) => {
+        [
+            concat!("institutional_investors_", $n, "_name"),
+            concat!("institutional_investors_", $n, "_buy"),
+            concat!("institutional_investors_", $n, "_sell"),
+            concat!("institutional_investors_", $n, "_difference"),
+            concat!("institutional_investors_", $n, "_name_en"),
+        ]
+    };
+}
+
+// the columns of the 三大法人買賣金額統計表 rows of a snapshot, BFI82U has six of them
+const SNAPSHOT_INSTITUTIONAL_INVESTORS: [[&str; 5]; 6] = [
+    snapshot_columns!(1),
+    snapshot_columns!(2),
+    snapshot_columns!(3),
+    snapshot_columns!(4),
+    snapshot_columns!(5),
+    snapshot_columns!(6),
+];
+
+// the row is written flat, csv has no nested values
+impl Serialize for DailyMarketSnapshot {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let investors = self.institutional_investors.as_deref().unwrap_or_default();
+        if investors.len() > SNAPSHOT_INSTITUTIONAL_INVESTORS.len() {
+            return Err(S::Error::custom(format!(
+                "{} institutional investors rows of {}",
+                investors.len(),
+                self.date
+            )));
+        }
+
+        let mut row = serializer.serialize_struct(
+            "DailyMarketSnapshot",
+            6 + 5 * SNAPSHOT_INSTITUTIONAL_INVESTORS.len(),
+        )?;
+        row.serialize_field("date", &self.date)?;
+        row.serialize_field("summary_volume", &self.summary.volume)?;
+        row.serialize_field("summary_value", &self.summary.value)?;
+        row.serialize_field("summary_transactions", &self.summary.transactions)?;
+        row.serialize_field("summary_taiex", &self.summary.taiex)?;
+        row.serialize_field("summary_change", &self.summary.change)?;
+        for (n, columns) in SNAPSHOT_INSTITUTIONAL_INVESTORS.iter().enumerate() {
+            let investor = investors.get(n);
+            row.serialize_field(columns[0], &investor.map(|i| &i.name))?;
+            row.serialize_field(columns[1], &investor.map(|i| i.buy))?;
+            row.serialize_field(columns[2], &investor.map(|i| i.sell))?;
+            row.serialize_field(columns[3], &investor.map(|i| i.difference))?;
+            row.serialize_field(columns[4], &investor.and_then(|i| i.name_en.as_ref()))?;
+        }
+        row.end()
+    }
+}
+
+// 發行量加權股價指數歷史資料
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TaiexHistory {
+    // the aliases are the OpenAPI field names
+    #[serde(alias = "Date", deserialize_with = "parse_roc_date")]
+    date: NaiveDate,
+    #[serde(alias = "OpeningIndex", deserialize_with = "parse_decimal")]
+    open: Decimal,
+    #[serde(alias = "HighestIndex", deserialize_with = "parse_decimal")]
+    high: Decimal,
+    #[serde(alias = "LowestIndex", deserialize_with = "parse_decimal")]
+    low: Decimal,
+    #[serde(alias = "ClosingIndex", deserialize_with = "parse_decimal")]
+    close: Decimal,
+}
+
+impl TaiexHistory {
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+}
+
+#[cfg(feature = "arrow")]
+crate::columnar::arrow_row!(
+    TaiexHistory,
+    "taiex_history",
+    [date, open, high, low, close]
+);
+
+// STOCK_DAY_ALL only serves the latest trading day
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DailyQuoteResponse {
+    pub stat: String,
+    #[serde(deserialize_with = "parse_date")]
+    pub date: NaiveDate,
+    pub fields: Vec<String>,
+    pub data: Vec<DailyQuote>,
+}
+
+// 每日收盤行情
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DailyQuote {
+    // the aliases are the OpenAPI field names
+    #[serde(alias = "Code", deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(alias = "Name", deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(alias = "TradeVolume", deserialize_with = "parse_u64")]
+    volume: u64,
+    #[serde(alias = "TradeValue", deserialize_with = "parse_u64")]
+    value: u64,
+    // -- when there are no trades
+    #[serde(alias = "OpeningPrice", deserialize_with = "parse_cell")]
+    open: Cell<Decimal>,
+    #[serde(alias = "HighestPrice", deserialize_with = "parse_cell")]
+    high: Cell<Decimal>,
+    #[serde(alias = "LowestPrice", deserialize_with = "parse_cell")]
+    low: Cell<Decimal>,
+    #[serde(alias = "ClosingPrice", deserialize_with = "parse_cell")]
+    close: Cell<Decimal>,
+    #[serde(alias = "Change", deserialize_with = "parse_cell")]
+    change: Cell<Decimal>,
+    #[serde(alias = "Transaction", deserialize_with = "parse_u64")]
+    transactions: u64,
+}
+
+impl DailyQuote {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn close(&self) -> Option<Decimal> {
+        self.close.value()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ForeignHoldingResponse {
+    pub stat: String,
+    #[serde(deserialize_with = "parse_date")]
+    pub date: NaiveDate,
+    pub fields: Vec<String>,
+    pub data: Vec<ForeignHolding>,
+}
+
+// 外資及陸資投資持股統計
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ForeignHolding {
+    // the aliases are the OpenAPI field names
+    #[serde(alias = "Code", deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(alias = "Name", deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(alias = "ISINCode", deserialize_with = "clean_string")]
+    isin: String,
+    #[serde(alias = "IssuedShares", deserialize_with = "parse_u64")]
+    issued_shares: u64,
+    #[serde(alias = "AvailableShares", deserialize_with = "parse_u64")]
+    available_shares: u64,
+    #[serde(alias = "SharesHeld", deserialize_with = "parse_u64")]
+    shares_held: u64,
+    #[serde(alias = "AvailableInvestPer", deserialize_with = "parse_decimal")]
+    available_percentage: Decimal,
+    #[serde(alias = "SharesHeldPer", deserialize_with = "parse_decimal")]
+    shares_held_percentage: Decimal,
+    #[serde(alias = "Upperlimit", deserialize_with = "parse_decimal")]
+    upper_limit_percentage: Decimal,
+    // -- for industries closed to mainland investors
+    #[serde(alias = "ChineseUpperlimit", deserialize_with = "parse_cell")]
+    mainland_upper_limit_percentage: Cell<Decimal>,
+    // 與前日異動原因 and 最近一次上市公司申報外資持股異動日期, which the OpenAPI leaves out
+    #[serde(default, serialize_with = "empty_cell")]
+    change_reason: IgnoredAny,
+    #[serde(default, serialize_with = "empty_cell")]
+    last_filing_date: IgnoredAny,
+}
+
+impl ForeignHolding {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn shares_held_percentage(&self) -> Decimal {
+        self.shares_held_percentage
+    }
+}
+
+// MI_INDEX with type=IND only has the 價格指數 table in data1
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SectorIndexResponse {
+    pub stat: String,
+    #[serde(deserialize_with = "parse_date")]
+    pub date: NaiveDate,
+    pub fields1: Vec<String>,
+    pub data1: Vec<SectorIndex>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SectorIndex {
+    #[serde(deserialize_with = "clean_string")]
+    name: String,
+    #[serde(deserialize_with = "parse_decimal")]
+    close: Decimal,
+    #[serde(deserialize_with = "parse_price_direction")]
+    direction: PriceDirection,
+    #[serde(deserialize_with = "parse_decimal")]
+    change_points: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    change_percentage: Decimal,
+    #[serde(deserialize_with = "clean_string")]
+    note: String,
+}
+
+impl SectorIndex {
+    pub fn index(&self) -> Option<Index> {
+        Index::from_name(&self.name)
+    }
+
+    pub fn change(&self) -> Decimal {
+        match self.direction {
+            PriceDirection::Down => -self.change_points,
+            _ => self.change_points,
+        }
+    }
+}
+
+// 漲跌(+/-)
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum PriceDirection {
+    Up,
+    Down,
+    Flat,
+    // X means the change cannot be compared, e.g. the first day of an index
+    NotComparable,
+}
+
+// 除權除息預告表
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ExRightsSchedule {
+    #[serde(deserialize_with = "parse_roc_date")]
+    date: NaiveDate,
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_right_type")]
+    right_type: RightType,
+    #[serde(deserialize_with = "parse_decimal")]
+    stock_dividend_ratio: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    cash_capital_increase_ratio: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    subscription_price: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    cash_dividend: Decimal,
+    // links to the detail page and the reference price calculator
+    #[serde(serialize_with = "empty_cell")]
+    detail: IgnoredAny,
+    #[serde(serialize_with = "empty_cell")]
+    reference_price_calculator: IgnoredAny,
+    #[serde(deserialize_with = "clean_string")]
+    latest_report_period: String,
+    #[serde(deserialize_with = "clean_string")]
+    latest_net_asset_value: String,
+    #[serde(deserialize_with = "clean_string")]
+    latest_earnings_per_share: String,
+}
+
+impl ExRightsSchedule {
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn right_type(&self) -> RightType {
+        self.right_type
+    }
+
+    pub fn stock_dividend_ratio(&self) -> Decimal {
+        self.stock_dividend_ratio
+    }
+
+    pub fn cash_capital_increase_ratio(&self) -> Decimal {
+        self.cash_capital_increase_ratio
+    }
+
+    pub fn subscription_price(&self) -> Decimal {
+        self.subscription_price
+    }
+
+    pub fn cash_dividend(&self) -> Decimal {
+        self.cash_dividend
+    }
+
+    pub fn latest_report_period(&self) -> &str {
+        &self.latest_report_period
+    }
+
+    pub fn latest_net_asset_value(&self) -> &str {
+        &self.latest_net_asset_value
+    }
+
+    pub fn latest_earnings_per_share(&self) -> &str {
+        &self.latest_earnings_per_share
+    }
+}
+
+// 除權除息計算結果表
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ExRightsResult {
+    #[serde(deserialize_with = "parse_roc_date")]
+    date: NaiveDate,
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_decimal")]
+    previous_close: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    reference_price: Decimal,
+    // 權值+息值, the stock dividend value plus the cash dividend
+    #[serde(deserialize_with = "parse_decimal")]
+    rights_and_dividend_value: Decimal,
+    #[serde(deserialize_with = "parse_right_type")]
+    right_type: RightType,
+    #[serde(deserialize_with = "parse_decimal")]
+    limit_up: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    limit_down: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    opening_reference_price: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    dividend_deducted_reference_price: Decimal,
+    // link to the detail page
+    #[serde(serialize_with = "empty_cell")]
+    detail: IgnoredAny,
+    #[serde(deserialize_with = "clean_string")]
+    latest_report_period: String,
+    #[serde(deserialize_with = "clean_string")]
+    latest_net_asset_value: String,
+    #[serde(deserialize_with = "clean_string")]
+    latest_earnings_per_share: String,
+}
+
+impl ExRightsResult {
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn previous_close(&self) -> Decimal {
+        self.previous_close
+    }
+
+    pub fn reference_price(&self) -> Decimal {
+        self.reference_price
+    }
+
+    pub fn rights_and_dividend_value(&self) -> Decimal {
+        self.rights_and_dividend_value
+    }
+
+    pub fn right_type(&self) -> RightType {
+        self.right_type
+    }
+
+    pub fn limit_up(&self) -> Decimal {
+        self.limit_up
+    }
+
+    pub fn limit_down(&self) -> Decimal {
+        self.limit_down
+    }
+
+    pub fn opening_reference_price(&self) -> Decimal {
+        self.opening_reference_price
+    }
+
+    pub fn dividend_deducted_reference_price(&self) -> Decimal {
+        self.dividend_deducted_reference_price
+    }
+
+    pub fn latest_report_period(&self) -> &str {
+        &self.latest_report_period
+    }
+
+    pub fn latest_net_asset_value(&self) -> &str {
+        &self.latest_net_asset_value
+    }
+
+    pub fn latest_earnings_per_share(&self) -> &str {
+        &self.latest_earnings_per_share
+    }
+
+    pub fn cash_dividend(&self) -> Option<Decimal> {
+        match self.right_type {
+            RightType::Dividend => Some(self.rights_and_dividend_value),
+            RightType::Rights => Some(Decimal::ZERO),
+            RightType::RightsAndDividend => None,
+        }
+    }
+}
+
+// 權/息
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum RightType {
+    Rights,
+    Dividend,
+    RightsAndDividend,
+}
+
+// 減資恢復買賣參考價格
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CapitalReduction {
+    #[serde(deserialize_with = "parse_roc_date")]
+    resume_date: NaiveDate,
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_decimal")]
+    last_close: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    reference_price: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    limit_up: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    limit_down: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    opening_reference_price: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    ex_rights_reference_price: Decimal,
+    #[serde(deserialize_with = "clean_string")]
+    reason: String,
+    // link to the detail page
+    #[serde(serialize_with = "empty_cell")]
+    detail: IgnoredAny,
+}
+
+impl CapitalReduction {
+    pub fn into_event(self) -> CorporateEvent {
+        CorporateEvent::CapitalReduction {
+            stock_id: self.stock_id,
+            stock_name: self.stock_name,
+            effective_date: self.resume_date,
+            last_close: self.last_close,
+            reference_price: self.reference_price,
+            reason: self.reason,
+        }
+    }
+}
+
+// 暫停交易及恢復交易
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TradingSuspension {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_roc_date")]
+    suspend_date: NaiveDate,
+    #[serde(deserialize_with = "clean_string")]
+    suspend_time: String,
+    #[serde(deserialize_with = "parse_optional_roc_date")]
+    resume_date: Option<NaiveDate>,
+    #[serde(deserialize_with = "clean_string")]
+    resume_time: String,
+}
+
+impl TradingSuspension {
+    // a suspension which has been resumed turns into two events
+    pub fn into_events(self) -> Vec<CorporateEvent> {
+        let mut ret = vec![CorporateEvent::Suspension {
+            stock_id: self.stock_id.clone(),
+            stock_name: self.stock_name.clone(),
+            effective_date: self.suspend_date,
+            resume_date: self.resume_date,
+        }];
+
+        if let Some(resume_date) = self.resume_date {
+            ret.push(CorporateEvent::Resumption {
+                stock_id: self.stock_id,
+                stock_name: self.stock_name,
+                effective_date: resume_date,
+            });
+        }
+
+        ret
+    }
+}
+
+// 變更面額恢復買賣參考價格
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ParValueChange {
+    #[serde(deserialize_with = "parse_roc_date")]
+    resume_date: NaiveDate,
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_decimal")]
+    last_close: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    reference_price: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    limit_up: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    limit_down: Decimal,
+    #[serde(deserialize_with = "parse_decimal")]
+    opening_reference_price: Decimal,
+    // link to the detail page
+    #[serde(serialize_with = "empty_cell")]
+    detail: IgnoredAny,
+}
+
+impl ParValueChange {
+    pub fn into_event(self) -> CorporateEvent {
+        CorporateEvent::ParValueChange {
+            stock_id: self.stock_id,
+            stock_name: self.stock_name,
+            effective_date: self.resume_date,
+            last_close: self.last_close,
+            reference_price: self.reference_price,
+        }
+    }
+}
+
+// 注意股票
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct AttentionStock {
+    #[serde(deserialize_with = "parse_u64")]
+    number: u64,
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_u64")]
+    cumulative_count: u64,
+    #[serde(deserialize_with = "parse_reasons", serialize_with = "join_reasons")]
+    reasons: Vec<String>,
+    #[serde(deserialize_with = "parse_roc_date")]
+    date: NaiveDate,
+    #[serde(deserialize_with = "parse_optional_decimal")]
+    close: Option<Decimal>,
+    // -- when there are no earnings
+    #[serde(deserialize_with = "parse_cell")]
+    price_earnings_ratio: Cell<Decimal>,
+}
+
+impl AttentionStock {
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn cumulative_count(&self) -> u64 {
+        self.cumulative_count
+    }
+
+    pub fn reasons(&self) -> &[String] {
+        &self.reasons
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn close(&self) -> Option<Decimal> {
+        self.close
+    }
+
+    pub fn price_earnings_ratio(&self) -> Option<Decimal> {
+        self.price_earnings_ratio.value()
+    }
+}
+
+// 處置股票
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DispositionStock {
+    #[serde(deserialize_with = "parse_u64")]
+    number: u64,
+    #[serde(deserialize_with = "parse_roc_date")]
+    announced_date: NaiveDate,
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_u64")]
+    cumulative_count: u64,
+    #[serde(deserialize_with = "parse_reasons", serialize_with = "join_reasons")]
+    reasons: Vec<String>,
+    #[serde(deserialize_with = "parse_roc_date_range")]
+    period: (NaiveDate, NaiveDate),
+    #[serde(deserialize_with = "parse_disposition_measure")]
+    measure: DispositionMeasure,
+    #[serde(deserialize_with = "clean_string")]
+    content: String,
+    #[serde(deserialize_with = "clean_string")]
+    note: String,
+}
+
+// the period is written as two columns, csv has no nested values
+impl Serialize for DispositionStock {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut row = serializer.serialize_struct("DispositionStock", 11)?;
+        row.serialize_field("number", &self.number)?;
+        row.serialize_field("announced_date", &self.announced_date)?;
+        row.serialize_field("stock_id", &self.stock_id)?;
+        row.serialize_field("stock_name", &self.stock_name)?;
+        row.serialize_field("cumulative_count", &self.cumulative_count)?;
+        row.serialize_field("reasons", &self.reasons.join("\n"))?;
+        row.serialize_field("period_start", &self.period.0)?;
+        row.serialize_field("period_end", &self.period.1)?;
+        row.serialize_field("measure", &self.measure)?;
+        row.serialize_field("content", &self.content)?;
+        row.serialize_field("note", &self.note)?;
+        row.end()
+    }
+}
+
+impl DispositionStock {
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    pub fn announced_date(&self) -> NaiveDate {
+        self.announced_date
+    }
+
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn cumulative_count(&self) -> u64 {
+        self.cumulative_count
+    }
+
+    pub fn reasons(&self) -> &[String] {
+        &self.reasons
+    }
+
+    pub fn period(&self) -> (NaiveDate, NaiveDate) {
+        self.period
+    }
+
+    pub fn measure(&self) -> DispositionMeasure {
+        self.measure
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn note(&self) -> &str {
+        &self.note
+    }
+
+    pub fn matching_interval(&self) -> MatchingInterval {
+        // 二十分鐘 has to be checked before 十分鐘
+        if self.content.contains("二十分鐘") || self.content.contains("20分鐘") {
+            MatchingInterval::TwentyMinutes
+        } else if self.content.contains("十分鐘") || self.content.contains("10分鐘") {
+            MatchingInterval::TenMinutes
+        } else if self.content.contains("五分鐘") || self.content.contains("5分鐘") {
+            MatchingInterval::FiveMinutes
+        } else {
+            MatchingInterval::Unknown
+        }
+    }
+}
+
+// 處置措施
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum DispositionMeasure {
+    First,
+    Second,
+}
+
+// 分盤集合競價的撮合間隔
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum MatchingInterval {
+    FiveMinutes,
+    TenMinutes,
+    TwentyMinutes,
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DayTradingResponse {
+    pub stat: String,
+    #[serde(deserialize_with = "parse_date")]
+    pub date: NaiveDate,
+    pub title: String,
+    pub fields: Vec<String>,
+    pub data: Vec<DayTradingSummary>,
+    #[serde(rename = "creditFields")]
+    pub stock_fields: Vec<String>,
+    #[serde(rename = "creditList")]
+    pub stocks: Vec<DayTrading>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DayTradingSummary {
+    #[serde(deserialize_with = "parse_u64")]
+    volume: u64,
+    #[serde(deserialize_with = "parse_decimal")]
+    volume_percentage: Decimal,
+    #[serde(deserialize_with = "parse_u64")]
+    buy_value: u64,
+    #[serde(deserialize_with = "parse_decimal")]
+    buy_value_percentage: Decimal,
+    #[serde(deserialize_with = "parse_u64")]
+    sell_value: u64,
+    #[serde(deserialize_with = "parse_decimal")]
+    sell_value_percentage: Decimal,
+}
+
+impl DayTradingSummary {
+    pub fn volume(&self) -> u64 {
+        self.volume
+    }
+
+    pub fn volume_percentage(&self) -> Decimal {
+        self.volume_percentage
+    }
+
+    pub fn buy_value(&self) -> u64 {
+        self.buy_value
+    }
+
+    pub fn buy_value_percentage(&self) -> Decimal {
+        self.buy_value_percentage
+    }
+
+    pub fn sell_value(&self) -> u64 {
+        self.sell_value
+    }
+
+    pub fn sell_value_percentage(&self) -> Decimal {
+        self.sell_value_percentage
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DayTrading {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_day_trading_suspension")]
+    suspension: DayTradingSuspension,
+    #[serde(deserialize_with = "parse_u64")]
+    volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    buy_value: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sell_value: u64,
+}
+
+impl DayTrading {
+    pub fn stock_id(&self) -> &str {
+        &self.stock_id
+    }
+
+    pub fn stock_name(&self) -> &str {
+        &self.stock_name
+    }
+
+    pub fn suspension(&self) -> DayTradingSuspension {
+        self.suspension
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.volume
+    }
+
+    pub fn buy_value(&self) -> u64 {
+        self.buy_value
+    }
+
+    pub fn sell_value(&self) -> u64 {
+        self.sell_value
+    }
+}
+
+// 暫停現股賣出後現款買進當沖註記
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum DayTradingSuspension {
+    Normal,
+    SellFirstSuspended,
+}
+
+// tpex.org.tw responses have no stat, an empty aaData means no data
+#[derive(Debug, Deserialize)]
+pub struct TpexResponse {
+    #[serde(
+        rename = "reportDate",
+        deserialize_with = "parse_optional_roc_date",
+        default
+    )]
+    pub date: Option<NaiveDate>,
+    #[serde(rename = "aaData")]
+    pub data: Vec<TpexData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", untagged)]
+pub enum TpexData {
+    TotalInstitutionalInvestors(TotalInstitutionalInvestors),
+    SingleStockInstitutionalInvestors(TpexSingleStockInstitutionalInvestors),
+}
+
+// 上櫃三大法人買賣明細資訊
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TpexSingleStockInstitutionalInvestors {
+    #[serde(deserialize_with = "clean_string")]
+    stock_id: String,
+    #[serde(deserialize_with = "clean_string")]
+    stock_name: String,
+    #[serde(deserialize_with = "parse_u64")]
+    foreign_investor_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    foreign_investor_sell: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    foreign_investor_difference: i64,
+    #[serde(deserialize_with = "parse_u64")]
+    foreign_dealer_self_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    foreign_dealer_self_sell: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    foreign_dealer_self_difference: i64,
+    // 外資及陸資合計 is the sum of the two above, so it is left out of the rows
+    #[serde(deserialize_with = "parse_u64")]
+    _foreign_total_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    _foreign_total_sell: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    _foreign_total_difference: i64,
+    #[serde(deserialize_with = "parse_u64")]
+    investment_trust_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    investment_trust_sell: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    investment_trust_difference: i64,
+    #[serde(deserialize_with = "parse_u64")]
+    dealer_self_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    dealer_self_sell: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    dealer_self_difference: i64,
+    #[serde(deserialize_with = "parse_u64")]
+    dealer_hedging_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    dealer_hedging_sell: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    dealer_hedging_difference: i64,
+    // the rows only keep the difference of 自營商合計
+    #[serde(deserialize_with = "parse_u64")]
+    _dealer_total_buy: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    _dealer_total_sell: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    dealer_total_difference: i64,
+    #[serde(deserialize_with = "parse_i64")]
+    total_difference: i64,
+}
+
+impl TpexSingleStockInstitutionalInvestors {
+    pub fn into_single_stock(self) -> SingleStockInstitutionalInvestors {
+        SingleStockInstitutionalInvestors {
+            stock_id: self.stock_id,
+            stock_name: self.stock_name,
+            stock_name_en: None,
+            foreign_investor_buy: self.foreign_investor_buy,
+            foreign_investor_sell: self.foreign_investor_sell,
+            foreign_investor_difference: self.foreign_investor_difference,
+            foreign_dealer_self_buy: self.foreign_dealer_self_buy,
+            foreign_dealer_self_sell: self.foreign_dealer_self_sell,
+            foreign_dealer_self_difference: self.foreign_dealer_self_difference,
+            investment_trust_buy: self.investment_trust_buy,
+            investment_trust_sell: self.investment_trust_sell,
+            investment_trust_difference: self.investment_trust_difference,
+            dealer_total_difference: self.dealer_total_difference,
+            dealer_self_buy: self.dealer_self_buy,
+            dealer_self_sell: self.dealer_self_sell,
+            dealer_self_difference: self.dealer_self_difference,
+            dealer_hedging_buy: self.dealer_hedging_buy,
+            dealer_hedging_sell: self.dealer_hedging_sell,
+            dealer_difference: self.dealer_hedging_difference,
+            total_difference: self.total_difference,
+        }
+    }
+}
+
+const TWSE_DATETIME_FORMAT: &'static str = "%Y%m%d";
+const TWSE_TIME_FORMAT: &str = "%H:%M:%S";
+// the Republic of China calendar starts from 1912, e.g. 110/02/05 is 2021/02/05
+const ROC_YEAR_OFFSET: i32 = 1911;
+
+fn parse_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    NaiveDate::parse_from_str(&s, TWSE_DATETIME_FORMAT).map_err(serde::de::Error::custom)
+}
+
+fn parse_roc_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    roc_date(&s).ok_or_else(|| serde::de::Error::custom(format!("invalid ROC date {}", s)))
+}
+
+fn parse_optional_roc_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.trim() {
+        "" | "--" => Ok(None),
+        _ => roc_date(&s)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid ROC date {}", s))),
+    }
+}
+
+// e.g. 110/02/08~110/02/22
+fn parse_roc_date_range<'de, D>(deserializer: D) -> Result<(NaiveDate, NaiveDate), D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    let mut dates = s.split(['~', '～']).map(roc_date);
+    match (dates.next(), dates.next(), dates.next()) {
+        (Some(Some(start)), Some(Some(end)), None) => Ok((start, end)),
+        _ => Err(serde::de::Error::custom(format!(
+            "invalid ROC date range {}",
+            s
+        ))),
+    }
+}
+
+// accepts both 110/02/05 and 110年02月05日
+fn roc_date(s: &str) -> Option<NaiveDate> {
+    // the OpenAPI packs the date without separators, e.g. 1100205
+    if s.len() > 4 && s.chars().all(|c| c.is_ascii_digit()) {
+        let (year, month_day) = s.split_at(s.len() - 4);
+        let (month, day) = month_day.split_at(2);
+        return NaiveDate::from_ymd_opt(
+            year.parse::<i32>().ok()? + ROC_YEAR_OFFSET,
+            month.parse().ok()?,
+            day.parse().ok()?,
+        );
+    }
+
+    let mut parts = s
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<u32>().ok());
+
+    let year = parts.next()??;
+    let month = parts.next()??;
+    let day = parts.next()??;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    NaiveDate::from_ymd_opt(year as i32 + ROC_YEAR_OFFSET, month, day)
+}
+
+// the query format of tpex.org.tw, e.g. 2021/02/05 is 110/02/05
+pub(crate) fn format_roc_date(date: NaiveDate) -> String {
+    format!("{}/{}", date.year() - ROC_YEAR_OFFSET, date.format("%m/%d"))
+}
+
+fn parse_time<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(s.trim(), TWSE_TIME_FORMAT).map_err(serde::de::Error::custom)
+}
+
+pub(crate) fn clean_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Ok(normalize_name(&s))
+}
+
+// keeps the single spaces of English names, e.g. TAIWAN CEMENT, but trims and collapses
+// every kind of whitespace, TWSE pads some names with U+3000 or NBSP,
+// the nfkc feature also turns full-width letters and digits into ASCII, e.g. ＫＹ into KY
+pub(crate) fn normalize_name(s: &str) -> String {
+    #[cfg(feature = "nfkc")]
+    let s: String = s.nfkc().collect();
+    s.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+pub(crate) fn parse_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    cell_value(&s)
+}
+
+// prices, percentages and dividends, e.g. 1,415.00, +0.50 or <p style= color:red>+13.36</p>
+pub(crate) fn parse_decimal<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    cell_value(&s)
+}
+
+// -- and X mean there is no price, e.g. no trades or not comparable, see Cell
+pub(crate) fn parse_optional_decimal<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Ok(cell::parse(&s).map_err(serde::de::Error::custom)?.value())
+}
+
+// reasons are separated by line breaks, e.g. 第一款<br>第五款
+fn parse_reasons<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Ok(s.replace("<br/>", "\n")
+        .replace("<br>", "\n")
+        .split('\n')
+        .map(|r| strip_html(r).trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect())
+}
+
+fn join_reasons<S>(reasons: &[String], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&reasons.join("\n"))
+}
+
+// the link and checkbox columns are written as empty cells to keep the columns of fields
+fn empty_cell<S>(_: &IgnoredAny, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str("")
+}
+
+fn parse_disposition_measure<'de, D>(deserializer: D) -> Result<DispositionMeasure, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.trim() {
+        "第一次處置" => Ok(DispositionMeasure::First),
+        "第二次處置" => Ok(DispositionMeasure::Second),
+        other => Err(serde::de::Error::custom(format!(
+            "unknown disposition measure {}",
+            other
+        ))),
+    }
+}
+
+fn parse_day_trading_suspension<'de, D>(deserializer: D) -> Result<DayTradingSuspension, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.trim() {
+        "" => Ok(DayTradingSuspension::Normal),
+        "Y" => Ok(DayTradingSuspension::SellFirstSuspended),
+        other => Err(serde::de::Error::custom(format!(
+            "unknown day trading suspension flag {}",
+            other
+        ))),
+    }
+}
+
+fn parse_securities_lending_type<'de, D>(deserializer: D) -> Result<SecuritiesLendingType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.trim() {
+        "競價" => Ok(SecuritiesLendingType::Competitive),
+        "定價" => Ok(SecuritiesLendingType::FixedRate),
+        "議借" => Ok(SecuritiesLendingType::Negotiated),
+        other => Err(serde::de::Error::custom(format!(
+            "unknown securities lending type {}",
+            other
+        ))),
+    }
+}
+
+fn parse_block_trade_type<'de, D>(deserializer: D) -> Result<BlockTradeType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.trim() {
+        "配對交易" => Ok(BlockTradeType::Paired),
+        "逐筆交易" => Ok(BlockTradeType::Continuous),
+        other => Err(serde::de::Error::custom(format!(
+            "unknown block trade type {}",
+            other
+        ))),
+    }
+}
+
+fn parse_price_direction<'de, D>(deserializer: D) -> Result<PriceDirection, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match strip_html(&s).trim() {
+        "+" => Ok(PriceDirection::Up),
+        "-" => Ok(PriceDirection::Down),
+        "" => Ok(PriceDirection::Flat),
+        "X" => Ok(PriceDirection::NotComparable),
+        other => Err(serde::de::Error::custom(format!(
+            "unknown price direction {}",
+            other
+        ))),
+    }
+}
+
+fn parse_right_type<'de, D>(deserializer: D) -> Result<RightType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match strip_html(&s).trim() {
+        "權" => Ok(RightType::Rights),
+        "息" => Ok(RightType::Dividend),
+        "權息" => Ok(RightType::RightsAndDividend),
+        other => Err(serde::de::Error::custom(format!(
+            "unknown right type {}",
+            other
+        ))),
+    }
+}
+
+// TWSE colors some cells, e.g. <p style ='color:red'>+</p>
+fn strip_html(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => ret.push(c),
+            _ => {}
+        }
+    }
+    ret
+}
+
+pub(crate) fn parse_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    cell_value(&s)
+}
+
+// the cells which always hold a number, a marker is an error
+fn cell_value<T: CellValue, E: serde::de::Error>(s: &str) -> Result<T, E> {
+    match cell::parse(s).map_err(E::custom)? {
+        Cell::Value(v) => Ok(v),
+        _ => Err(E::custom(format!("no value in cell {}", s))),
+    }
+}
+
+#[cfg(test)]
+use anyhow::{Context, Result};
+#[cfg(test)]
+use serde_json;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use serde::de::value::{Error as ValueError, StrDeserializer};
+
+    #[test]
+    fn test_response_with_total_institutional_investors() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "title":"110年02月05日 三大法人買賣金額統計表",
+            "fields":["單位名稱","買進金額","賣出金額","買賣差額"],
+            "date":"20210205",
+            "data":[
+                ["自營商(自行買賣)","3,720,692,397","2,657,469,622","1,063,222,775"],
+                ["自營商(避險)","7,930,149,338","6,161,180,878","1,768,968,460"],
+                ["投信","2,305,132,480","1,693,938,980","611,193,500"],
+                ["外資及陸資","73,576,875,154","79,202,203,729","-5,625,328,575"],
+                ["合計","87,532,849,369","89,714,793,209","-2,181,943,840"]
+            ],
+            "params":{"controller":"fund","format":null,"action":"BFI82U","lang":"zh","monthDate":"20210205","weekDate":"20210201","dayDate":"20210205"},
+            "notes":[
+                "自營商表示證券自營商專戶。",
+                "投信表示本國投資信託基金。",
+                "外資及陸資表示依「華僑及外國人投資證券管理辦法」及「大陸地區投資人來臺從事證券投資及期貨交易管理辦法」辦理登記等投資人。",
+                "本統計資訊含一般、零股、盤後定價、鉅額，不含拍賣、標購。",
+                "本資訊以當日原始成交情形統計，不以證券商申報錯帳、更正帳號等調整後資料統計。"
+            ]
+        }
+        "#;
+        let result: Response = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 5);
+
+        let wants = [
+            TotalInstitutionalInvestors {
+                name: "自營商(自行買賣)".to_string(),
+                name_en: None,
+                buy: 3720692397,
+                sell: 2657469622,
+                difference: 1063222775,
+            },
+            TotalInstitutionalInvestors {
+                name: "自營商(避險)".to_string(),
+                name_en: None,
+                buy: 7930149338,
+                sell: 6161180878,
+                difference: 1768968460,
+            },
+            TotalInstitutionalInvestors {
+                name: "投信".to_string(),
+                name_en: None,
+                buy: 2305132480,
+                sell: 1693938980,
+                difference: 611193500,
+            },
+            TotalInstitutionalInvestors {
+                name: "外資及陸資".to_string(),
+                name_en: None,
+                buy: 73576875154,
+                sell: 79202203729,
+                difference: -5625328575,
+            },
+            TotalInstitutionalInvestors {
+                name: "合計".to_string(),
+                name_en: None,
+                buy: 87532849369,
+                sell: 89714793209,
+                difference: -2181943840,
+            },
+        ];
+
+        for n in 0..result.data.len() {
+            if let Data::TotalInstitutionalInvestors(d) = &result.data[n] {
+                assert_eq!(d.buy, wants[n].buy);
+                assert_eq!(d.sell, wants[n].sell);
+                assert_eq!(d.difference, wants[n].difference);
+                assert_eq!(d.name, wants[n].name);
+            } else {
+                assert!(false, "data vector index {} cannot cast out", n);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_single_stock_institutional_investors() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 三大法人買賣超日報",
+            "fields":[
+                "證券代號",
+                "證券名稱",
+                "外陸資買進股數(不含外資自營商)",
+                "外陸資賣出股數(不含外資自營商)",
+                "外陸資買賣超股數(不含外資自營商)",
+                "外資自營商買進股數",
+                "外資自營商賣出股數",
+                "外資自營商買賣超股數",
+                "投信買進股數",
+                "投信賣出股數",
+                "投信買賣超股數",
+                "自營商買賣超股數",
+                "自營商買進股數(自行買賣)",
+                "自營商賣出股數(自行買賣)",
+                "自營商買賣超股數(自行買賣)",
+                "自營商買進股數(避險)",
+                "自營商賣出股數(避險)",
                 "自營商買賣超股數(避險)",
                 "三大法人買賣超股數"
             ],
             "data":[
-                ["1104","環泥            ","121,000","86,000","35,000","0","0","0","0","0","0","3,000","3,000","0","3,000","0","0","0","38,000"],
-                ["1108","幸福            ","46,000","30,000","16,000","0","0","0","0","0","0","2,000","2,000","0","2,000","0","0","0","18,000"],
-                ["1109","信大            ","32,000","25,000","7,000","0","0","0","0","0","0","0","0","0","0","0","0","0","7,000"],
-                ["1101B","台泥乙特        ","0","0","0","0","0","0","0","0","0","1,000","1,000","0","1,000","0","0","0","1,000"]
+                ["1104","環泥            ","121,000","86,000","35,000","0","0","0","0","0","0","3,000","3,000","0","3,000","0","0","0","38,000"],
+                ["1108","幸福            ","46,000","30,000","16,000","0","0","0","0","0","0","2,000","2,000","0","2,000","0","0","0","18,000"],
+                ["1109","信大            ","32,000","25,000","7,000","0","0","0","0","0","0","0","0","0","0","0","0","0","7,000"],
+                ["1101B","台泥乙特        ","0","0","0","0","0","0","0","0","0","1,000","1,000","0","1,000","0","0","0","1,000"]
+            ],
+            "selectType":"01",
+            "notes":[
+                "自營商表示證券自營商專戶。",
+                "投信表示本國投資信託基金。",
+                "外資及陸資表示依「華僑及外國人投資證券管理辦法」及「大陸地區投資人來臺從事證券投資及期貨交易管理辦法」辦理登記等投資人。",
+                "外資自營商買賣股數已計入自營商買賣股數，故不納入三大法人買賣股數之合計數計算。",
+                "本統計資訊含一般、零股、盤後定價、鉅額，不含拍賣、標購。",
+                "本資訊以當日原始成交情形統計，不以證券商申報錯帳、更正帳號等調整後資料統計。",
+                "ETF證券代號第六碼為K、M、S、C者，表示該ETF以外幣交易。"
+            ]
+        }
+        "#;
+        let result: Response = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 4);
+
+        let wants = [
+            SingleStockInstitutionalInvestors {
+                stock_id: "1104".to_string(),
+                stock_name: "環泥".to_string(),
+                stock_name_en: None,
+                foreign_investor_buy: 121000,
+                foreign_investor_sell: 86000,
+                foreign_investor_difference: 35000,
+                foreign_dealer_self_buy: 0,
+                foreign_dealer_self_sell: 0,
+                foreign_dealer_self_difference: 0,
+                investment_trust_buy: 0,
+                investment_trust_sell: 0,
+                investment_trust_difference: 0,
+                dealer_total_difference: 3000,
+                dealer_self_buy: 3000,
+                dealer_self_sell: 0,
+                dealer_self_difference: 3000,
+                dealer_hedging_buy: 0,
+                dealer_hedging_sell: 0,
+                dealer_difference: 0,
+                total_difference: 38000,
+            },
+            SingleStockInstitutionalInvestors {
+                stock_id: "1108".to_string(),
+                stock_name: "幸福".to_string(),
+                stock_name_en: None,
+                foreign_investor_buy: 46000,
+                foreign_investor_sell: 30000,
+                foreign_investor_difference: 16000,
+                foreign_dealer_self_buy: 0,
+                foreign_dealer_self_sell: 0,
+                foreign_dealer_self_difference: 0,
+                investment_trust_buy: 0,
+                investment_trust_sell: 0,
+                investment_trust_difference: 0,
+                dealer_total_difference: 2000,
+                dealer_self_buy: 2000,
+                dealer_self_sell: 0,
+                dealer_self_difference: 2000,
+                dealer_hedging_buy: 0,
+                dealer_hedging_sell: 0,
+                dealer_difference: 0,
+                total_difference: 18000,
+            },
+            SingleStockInstitutionalInvestors {
+                stock_id: "1109".to_string(),
+                stock_name: "信大".to_string(),
+                stock_name_en: None,
+                foreign_investor_buy: 32000,
+                foreign_investor_sell: 25000,
+                foreign_investor_difference: 7000,
+                foreign_dealer_self_buy: 0,
+                foreign_dealer_self_sell: 0,
+                foreign_dealer_self_difference: 0,
+                investment_trust_buy: 0,
+                investment_trust_sell: 0,
+                investment_trust_difference: 0,
+                dealer_total_difference: 0,
+                dealer_self_buy: 0,
+                dealer_self_sell: 0,
+                dealer_self_difference: 0,
+                dealer_hedging_buy: 0,
+                dealer_hedging_sell: 0,
+                dealer_difference: 0,
+                total_difference: 7000,
+            },
+            SingleStockInstitutionalInvestors {
+                stock_id: "1101B".to_string(),
+                stock_name: "台泥乙特".to_string(),
+                stock_name_en: None,
+                foreign_investor_buy: 0,
+                foreign_investor_sell: 0,
+                foreign_investor_difference: 0,
+                foreign_dealer_self_buy: 0,
+                foreign_dealer_self_sell: 0,
+                foreign_dealer_self_difference: 0,
+                investment_trust_buy: 0,
+                investment_trust_sell: 0,
+                investment_trust_difference: 0,
+                dealer_total_difference: 1000,
+                dealer_self_buy: 1000,
+                dealer_self_sell: 0,
+                dealer_self_difference: 1000,
+                dealer_hedging_buy: 0,
+                dealer_hedging_sell: 0,
+                dealer_difference: 0,
+                total_difference: 1000,
+            },
+            SingleStockInstitutionalInvestors {
+                stock_id: "1110".to_string(),
+                stock_name: "東泥".to_string(),
+                stock_name_en: None,
+                foreign_investor_buy: 28000,
+                foreign_investor_sell: 31000,
+                foreign_investor_difference: -3000,
+                foreign_dealer_self_buy: 0,
+                foreign_dealer_self_sell: 0,
+                foreign_dealer_self_difference: 0,
+                investment_trust_buy: 0,
+                investment_trust_sell: 0,
+                investment_trust_difference: 0,
+                dealer_total_difference: 0,
+                dealer_self_buy: 0,
+                dealer_self_sell: 0,
+                dealer_self_difference: 0,
+                dealer_hedging_buy: 0,
+                dealer_hedging_sell: 0,
+                dealer_difference: 0,
+                total_difference: -3000,
+            },
+        ];
+
+        for n in 0..result.data.len() {
+            if let Data::SingleStockInstitutionalInvestors(d) = &result.data[n] {
+                assert_eq!(d.stock_id, wants[n].stock_id);
+                assert_eq!(d.stock_name, wants[n].stock_name);
+                assert_eq!(d.foreign_investor_buy, wants[n].foreign_investor_buy);
+                assert_eq!(d.foreign_investor_sell, wants[n].foreign_investor_sell);
+                assert_eq!(
+                    d.foreign_investor_difference,
+                    wants[n].foreign_investor_difference
+                );
+                assert_eq!(d.foreign_dealer_self_buy, wants[n].foreign_dealer_self_buy);
+                assert_eq!(
+                    d.foreign_dealer_self_sell,
+                    wants[n].foreign_dealer_self_sell
+                );
+                assert_eq!(
+                    d.foreign_dealer_self_difference,
+                    wants[n].foreign_dealer_self_difference
+                );
+                assert_eq!(d.investment_trust_buy, wants[n].investment_trust_buy);
+                assert_eq!(d.investment_trust_sell, wants[n].investment_trust_sell);
+                assert_eq!(
+                    d.investment_trust_difference,
+                    wants[n].investment_trust_difference
+                );
+                assert_eq!(d.dealer_total_difference, wants[n].dealer_total_difference);
+                assert_eq!(d.dealer_self_buy, wants[n].dealer_self_buy);
+                assert_eq!(d.dealer_self_sell, wants[n].dealer_self_sell);
+                assert_eq!(d.dealer_self_difference, wants[n].dealer_self_difference);
+                assert_eq!(d.dealer_hedging_buy, wants[n].dealer_hedging_buy);
+                assert_eq!(d.dealer_hedging_sell, wants[n].dealer_hedging_sell);
+                assert_eq!(d.dealer_difference, wants[n].dealer_difference);
+                assert_eq!(d.total_difference, wants[n].total_difference);
+            } else {
+                assert!(false, "data vector index {} cannot cast out", n);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_foreign_investors_ranking() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 外資及陸資買賣超彙總表 (股)",
+            "fields":[
+                "",
+                "證券代號",
+                "證券名稱",
+                "外資及陸資(不含外資自營商)-買進股數",
+                "外資及陸資(不含外資自營商)-賣出股數",
+                "外資及陸資(不含外資自營商)-買賣超股數",
+                "外資自營商-買進股數",
+                "外資自營商-賣出股數",
+                "外資自營商-買賣超股數",
+                "外資及陸資-買進股數",
+                "外資及陸資-賣出股數",
+                "外資及陸資-買賣超股數"
+            ],
+            "data":[
+                [" ","2303","聯電            ","47,118,590","28,470,170","18,648,420","0","0","0","47,118,590","28,470,170","18,648,420"],
+                [" ","2330","台積電          ","11,236,062","23,590,435","-12,354,373","0","1,000","-1,000","11,236,062","23,591,435","-12,355,373"]
+            ],
+            "notes":[
+                "外資及陸資表示依「華僑及外國人投資證券管理辦法」及「大陸地區投資人來臺從事證券投資及期貨交易管理辦法」辦理登記等投資人。"
+            ]
+        }
+        "#;
+        let result: ReportResponse<ForeignInvestorsRanking> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            ForeignInvestorsRanking {
+                blank: IgnoredAny,
+                stock_id: "2303".to_string(),
+                stock_name: "聯電".to_string(),
+                foreign_investor_buy: 47118590,
+                foreign_investor_sell: 28470170,
+                foreign_investor_difference: 18648420,
+                foreign_dealer_self_buy: 0,
+                foreign_dealer_self_sell: 0,
+                foreign_dealer_self_difference: 0,
+                total_buy: 47118590,
+                total_sell: 28470170,
+                total_difference: 18648420,
+            },
+            ForeignInvestorsRanking {
+                blank: IgnoredAny,
+                stock_id: "2330".to_string(),
+                stock_name: "台積電".to_string(),
+                foreign_investor_buy: 11236062,
+                foreign_investor_sell: 23590435,
+                foreign_investor_difference: -12354373,
+                foreign_dealer_self_buy: 0,
+                foreign_dealer_self_sell: 1000,
+                foreign_dealer_self_difference: -1000,
+                total_buy: 11236062,
+                total_sell: 23591435,
+                total_difference: -12355373,
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(d.foreign_investor_buy, wants[n].foreign_investor_buy);
+            assert_eq!(d.foreign_investor_sell, wants[n].foreign_investor_sell);
+            assert_eq!(
+                d.foreign_investor_difference,
+                wants[n].foreign_investor_difference
+            );
+            assert_eq!(d.foreign_dealer_self_buy, wants[n].foreign_dealer_self_buy);
+            assert_eq!(
+                d.foreign_dealer_self_sell,
+                wants[n].foreign_dealer_self_sell
+            );
+            assert_eq!(
+                d.foreign_dealer_self_difference,
+                wants[n].foreign_dealer_self_difference
+            );
+            assert_eq!(d.total_buy, wants[n].total_buy);
+            assert_eq!(d.total_sell, wants[n].total_sell);
+            assert_eq!(d.total_difference, wants[n].total_difference);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_investment_trust_ranking() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 投信買賣超彙總表",
+            "fields":["","證券代號","證券名稱","買進股數","賣出股數","買賣超股數"],
+            "data":[
+                [" ","2409","友達            ","12,850,000","220,000","12,630,000"],
+                [" ","00881","國泰台灣5G+      ","0","4,520,000","-4,520,000"]
+            ],
+            "notes":["投信表示本國投資信託基金。"]
+        }
+        "#;
+        let result: ReportResponse<InvestmentTrustRanking> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            InvestmentTrustRanking {
+                blank: IgnoredAny,
+                stock_id: "2409".to_string(),
+                stock_name: "友達".to_string(),
+                buy: 12850000,
+                sell: 220000,
+                difference: 12630000,
+            },
+            InvestmentTrustRanking {
+                blank: IgnoredAny,
+                stock_id: "00881".to_string(),
+                stock_name: "國泰台灣5G+".to_string(),
+                buy: 0,
+                sell: 4520000,
+                difference: -4520000,
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(d.buy, wants[n].buy);
+            assert_eq!(d.sell, wants[n].sell);
+            assert_eq!(d.difference, wants[n].difference);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_dealers_ranking() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 自營商買賣超彙總表 (股)",
+            "fields":[
+                "證券代號",
+                "證券名稱",
+                "自營商(自行買賣)-買進股數",
+                "自營商(自行買賣)-賣出股數",
+                "自營商(自行買賣)-買賣超股數",
+                "自營商(避險)-買進股數",
+                "自營商(避險)-賣出股數",
+                "自營商(避險)-買賣超股數",
+                "自營商-買進股數",
+                "自營商-賣出股數",
+                "自營商-買賣超股數"
+            ],
+            "data":[
+                ["2330","台積電          ","426,000","389,000","37,000","1,083,000","1,542,611","-459,611","1,509,000","1,931,611","-422,611"],
+                ["1104","環泥            ","3,000","0","3,000","0","0","0","3,000","0","3,000"]
+            ],
+            "notes":["自營商表示證券自營商專戶。"]
+        }
+        "#;
+        let result: ReportResponse<DealersRanking> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            DealersRanking {
+                stock_id: "2330".to_string(),
+                stock_name: "台積電".to_string(),
+                dealer_self_buy: 426000,
+                dealer_self_sell: 389000,
+                dealer_self_difference: 37000,
+                dealer_hedging_buy: 1083000,
+                dealer_hedging_sell: 1542611,
+                dealer_hedging_difference: -459611,
+                total_buy: 1509000,
+                total_sell: 1931611,
+                total_difference: -422611,
+            },
+            DealersRanking {
+                stock_id: "1104".to_string(),
+                stock_name: "環泥".to_string(),
+                dealer_self_buy: 3000,
+                dealer_self_sell: 0,
+                dealer_self_difference: 3000,
+                dealer_hedging_buy: 0,
+                dealer_hedging_sell: 0,
+                dealer_hedging_difference: 0,
+                total_buy: 3000,
+                total_sell: 0,
+                total_difference: 3000,
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(d.dealer_self_buy, wants[n].dealer_self_buy);
+            assert_eq!(d.dealer_self_sell, wants[n].dealer_self_sell);
+            assert_eq!(d.dealer_self_difference, wants[n].dealer_self_difference);
+            assert_eq!(d.dealer_hedging_buy, wants[n].dealer_hedging_buy);
+            assert_eq!(d.dealer_hedging_sell, wants[n].dealer_hedging_sell);
+            assert_eq!(
+                d.dealer_hedging_difference,
+                wants[n].dealer_hedging_difference
+            );
+            assert_eq!(d.total_buy, wants[n].total_buy);
+            assert_eq!(d.total_sell, wants[n].total_sell);
+            assert_eq!(d.total_difference, wants[n].total_difference);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_trading_response() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 當日沖銷交易標的及成交量值",
+            "fields":[
+                "當日沖銷交易總成交股數",
+                "當日沖銷交易總成交股數占市場比重%",
+                "當日沖銷交易總買進成交金額",
+                "當日沖銷交易總買進成交金額占市場比重%",
+                "當日沖銷交易總賣出成交金額",
+                "當日沖銷交易總賣出成交金額占市場比重%"
+            ],
+            "data":[["1,101,316,000","19.50","48,313,245,130","16.91","48,421,086,550","16.95"]],
+            "creditFields":[
+                "證券代號",
+                "證券名稱",
+                "暫停現股賣出後現款買進當沖註記",
+                "當日沖銷交易成交股數",
+                "當日沖銷交易買進成交金額",
+                "當日沖銷交易賣出成交金額"
+            ],
+            "creditList":[
+                ["0050","元大台灣50      ","","1,012,000","138,617,350","138,753,950"],
+                ["1101","台泥            ","Y","2,163,000","93,588,200","93,650,300"]
+            ],
+            "creditNotes":["證券代號為「0」開頭者係ETF或受益證券。"],
+            "selectType":"All"
+        }
+        "#;
+        let result: DayTradingResponse = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 1);
+        assert_eq!(result.stocks.len(), 2);
+
+        let summary = &result.data[0];
+        assert_eq!(summary.volume, 1101316000);
+        assert_eq!(summary.volume_percentage, dec!(19.50));
+        assert_eq!(summary.buy_value, 48313245130);
+        assert_eq!(summary.buy_value_percentage, dec!(16.91));
+        assert_eq!(summary.sell_value, 48421086550);
+        assert_eq!(summary.sell_value_percentage, dec!(16.95));
+
+        let wants = [
+            DayTrading {
+                stock_id: "0050".to_string(),
+                stock_name: "元大台灣50".to_string(),
+                suspension: DayTradingSuspension::Normal,
+                volume: 1012000,
+                buy_value: 138617350,
+                sell_value: 138753950,
+            },
+            DayTrading {
+                stock_id: "1101".to_string(),
+                stock_name: "台泥".to_string(),
+                suspension: DayTradingSuspension::SellFirstSuspended,
+                volume: 2163000,
+                buy_value: 93588200,
+                sell_value: 93650300,
+            },
+        ];
+
+        for n in 0..result.stocks.len() {
+            let d = &result.stocks[n];
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(d.suspension, wants[n].suspension);
+            assert_eq!(d.volume, wants[n].volume);
+            assert_eq!(d.buy_value, wants[n].buy_value);
+            assert_eq!(d.sell_value, wants[n].sell_value);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_short_sale_balances() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 信用額度總量管制餘額表",
+            "fields":[
+                "股票代號",
+                "股票名稱",
+                "融券前日餘額",
+                "融券賣出",
+                "融券買進",
+                "融券現券",
+                "融券今日餘額",
+                "融券限額",
+                "借券賣出前日餘額",
+                "借券賣出當日賣出",
+                "借券賣出當日還券",
+                "借券賣出當日調整",
+                "借券賣出當日餘額",
+                "借券賣出次一營業日可限額",
+                "備註"
+            ],
+            "data":[
+                ["1101","台泥            ","3,046,000","183,000","297,000","0","2,932,000","1,349,506,045","24,108,404","1,195,000","1,326,000","0","23,977,404","119,264,916",""],
+                ["2330","台積電          ","4,178,000","257,000","375,000","1,000","4,059,000","6,482,400,000","60,470,611","1,530,000","3,042,000","-2,000","58,956,611","311,101,058","X"]
+            ],
+            "notes":["本表資訊含證券商及證券金融事業之融券資料及借券賣出資料。"]
+        }
+        "#;
+        let result: ReportResponse<ShortSaleBalances> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            ShortSaleBalances {
+                stock_id: "1101".to_string(),
+                stock_name: "台泥".to_string(),
+                margin_short_previous_balance: 3046000,
+                margin_short_sell: 183000,
+                margin_short_buy: 297000,
+                margin_short_redemption: 0,
+                margin_short_balance: 2932000,
+                margin_short_quota: 1349506045,
+                sbl_short_previous_balance: 24108404,
+                sbl_short_sell: 1195000,
+                sbl_short_return: 1326000,
+                sbl_short_adjustment: 0,
+                sbl_short_balance: 23977404,
+                sbl_short_quota: 119264916,
+                note: "".to_string(),
+            },
+            ShortSaleBalances {
+                stock_id: "2330".to_string(),
+                stock_name: "台積電".to_string(),
+                margin_short_previous_balance: 4178000,
+                margin_short_sell: 257000,
+                margin_short_buy: 375000,
+                margin_short_redemption: 1000,
+                margin_short_balance: 4059000,
+                margin_short_quota: 6482400000,
+                sbl_short_previous_balance: 60470611,
+                sbl_short_sell: 1530000,
+                sbl_short_return: 3042000,
+                sbl_short_adjustment: -2000,
+                sbl_short_balance: 58956611,
+                sbl_short_quota: 311101058,
+                note: "X".to_string(),
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(
+                d.margin_short_previous_balance,
+                wants[n].margin_short_previous_balance
+            );
+            assert_eq!(d.margin_short_sell, wants[n].margin_short_sell);
+            assert_eq!(d.margin_short_buy, wants[n].margin_short_buy);
+            assert_eq!(d.margin_short_redemption, wants[n].margin_short_redemption);
+            assert_eq!(d.margin_short_balance, wants[n].margin_short_balance);
+            assert_eq!(d.margin_short_quota, wants[n].margin_short_quota);
+            assert_eq!(
+                d.sbl_short_previous_balance,
+                wants[n].sbl_short_previous_balance
+            );
+            assert_eq!(d.sbl_short_sell, wants[n].sbl_short_sell);
+            assert_eq!(d.sbl_short_return, wants[n].sbl_short_return);
+            assert_eq!(d.sbl_short_adjustment, wants[n].sbl_short_adjustment);
+            assert_eq!(d.sbl_short_balance, wants[n].sbl_short_balance);
+            assert_eq!(d.sbl_short_quota, wants[n].sbl_short_quota);
+            assert_eq!(d.note, wants[n].note);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_securities_lending_transaction() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 借券成交明細",
+            "fields":["證券代號","證券名稱","交易方式","成交數量","費率(%)","借券期間(日)"],
+            "data":[
+                ["2330","台積電          ","競價","10,000","0.50","182"],
+                ["2303","聯電            ","議借","1,250,000","1.20","30"],
+                ["2409","友達            ","定價","3,000","0","182"]
+            ],
+            "notes":[]
+        }
+        "#;
+        let result: ReportResponse<SecuritiesLendingTransaction> =
+            serde_json::from_str(response)
+                .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 3);
+
+        let wants = [
+            SecuritiesLendingTransaction {
+                stock_id: "2330".to_string(),
+                stock_name: "台積電".to_string(),
+                transaction_type: SecuritiesLendingType::Competitive,
+                volume: 10000,
+                fee_rate: dec!(0.5),
+                duration_days: 182,
+            },
+            SecuritiesLendingTransaction {
+                stock_id: "2303".to_string(),
+                stock_name: "聯電".to_string(),
+                transaction_type: SecuritiesLendingType::Negotiated,
+                volume: 1250000,
+                fee_rate: dec!(1.2),
+                duration_days: 30,
+            },
+            SecuritiesLendingTransaction {
+                stock_id: "2409".to_string(),
+                stock_name: "友達".to_string(),
+                transaction_type: SecuritiesLendingType::FixedRate,
+                volume: 3000,
+                fee_rate: dec!(0.0),
+                duration_days: 182,
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(d.transaction_type, wants[n].transaction_type);
+            assert_eq!(d.volume, wants[n].volume);
+            assert_eq!(d.fee_rate, wants[n].fee_rate);
+            assert_eq!(d.duration_days, wants[n].duration_days);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_block_trade() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 鉅額交易日成交資訊",
+            "fields":["證券代號","證券名稱","交易別","成交價","成交股數","成交金額"],
+            "data":[
+                ["2330","台積電","配對交易","632.00","1,000,000","632,000,000"],
+                ["0056","元大高股息","逐筆交易","32.51","2,500,000","81,275,000"]
+            ],
+            "notes":[]
+        }
+        "#;
+        let result: ReportResponse<BlockTrade> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            BlockTrade {
+                stock_id: "2330".to_string(),
+                stock_name: "台積電".to_string(),
+                trade_type: BlockTradeType::Paired,
+                price: dec!(632.0),
+                volume: 1000000,
+                value: 632000000,
+            },
+            BlockTrade {
+                stock_id: "0056".to_string(),
+                stock_name: "元大高股息".to_string(),
+                trade_type: BlockTradeType::Continuous,
+                price: dec!(32.51),
+                volume: 2500000,
+                value: 81275000,
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(d.trade_type, wants[n].trade_type);
+            assert_eq!(d.price, wants[n].price);
+            assert_eq!(d.volume, wants[n].volume);
+            assert_eq!(d.value, wants[n].value);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_fixed_price_trading() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 盤後定價交易",
+            "fields":["證券代號","證券名稱","成交股數","成交筆數","成交金額","成交價","最後揭示買量","最後揭示賣量"],
+            "data":[
+                ["2330","台積電          ","341,000","103","215,512,000","632.00","12,000","0"],
+                ["2603","長榮            ","58,000","21","1,380,400","23.80","0","7,000"],
+                ["9958","世紀鋼          ","0","0","0","--","0","2,000"]
+            ],
+            "notes":[]
+        }
+        "#;
+        let result: ReportResponse<FixedPriceTrading> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 3);
+
+        let wants = [
+            FixedPriceTrading {
+                stock_id: "2330".to_string(),
+                stock_name: "台積電".to_string(),
+                volume: 341000,
+                transactions: 103,
+                value: 215512000,
+                price: Cell::Value(dec!(632.0)),
+                last_best_bid_volume: 12000,
+                last_best_ask_volume: 0,
+            },
+            FixedPriceTrading {
+                stock_id: "2603".to_string(),
+                stock_name: "長榮".to_string(),
+                volume: 58000,
+                transactions: 21,
+                value: 1380400,
+                price: Cell::Value(dec!(23.8)),
+                last_best_bid_volume: 0,
+                last_best_ask_volume: 7000,
+            },
+            FixedPriceTrading {
+                stock_id: "9958".to_string(),
+                stock_name: "世紀鋼".to_string(),
+                volume: 0,
+                transactions: 0,
+                value: 0,
+                price: Cell::NotApplicable,
+                last_best_bid_volume: 0,
+                last_best_ask_volume: 2000,
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(d.volume, wants[n].volume);
+            assert_eq!(d.transactions, wants[n].transactions);
+            assert_eq!(d.value, wants[n].value);
+            assert_eq!(d.price, wants[n].price);
+            assert_eq!(d.last_best_bid_volume, wants[n].last_best_bid_volume);
+            assert_eq!(d.last_best_ask_volume, wants[n].last_best_ask_volume);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_odd_lot_trading() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 盤後零股交易",
+            "fields":["證券代號","證券名稱","成交股數","成交筆數","成交金額","成交價格","最後揭示買價","最後揭示賣價"],
+            "data":[
+                ["2330","台積電          ","23,411","412","14,795,752","632.00","631.00","633.00"],
+                ["0050","元大台灣50      ","5,120","87","703,488","137.40","137.35","137.45"],
+                ["2603","長榮            ","120","3","2,856","24","23","24"],
+                ["9958","世紀鋼          ","0","0","0","--","--","61.20"]
+            ],
+            "notes":[]
+        }
+        "#;
+        let result: ReportResponse<OddLotTrading> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 4);
+
+        let wants = [
+            OddLotTrading {
+                stock_id: "2330".to_string(),
+                stock_name: "台積電".to_string(),
+                volume: 23411,
+                transactions: 412,
+                value: 14795752,
+                price: Cell::Value(dec!(632.0)),
+                last_best_bid_price: Cell::Value(dec!(631.0)),
+                last_best_ask_price: Cell::Value(dec!(633.0)),
+            },
+            OddLotTrading {
+                stock_id: "0050".to_string(),
+                stock_name: "元大台灣50".to_string(),
+                volume: 5120,
+                transactions: 87,
+                value: 703488,
+                price: Cell::Value(dec!(137.4)),
+                last_best_bid_price: Cell::Value(dec!(137.35)),
+                last_best_ask_price: Cell::Value(dec!(137.45)),
+            },
+            // integer prices, which fit the columns of the fixed price trading too
+            OddLotTrading {
+                stock_id: "2603".to_string(),
+                stock_name: "長榮".to_string(),
+                volume: 120,
+                transactions: 3,
+                value: 2856,
+                price: Cell::Value(dec!(24)),
+                last_best_bid_price: Cell::Value(dec!(23)),
+                last_best_ask_price: Cell::Value(dec!(24)),
+            },
+            OddLotTrading {
+                stock_id: "9958".to_string(),
+                stock_name: "世紀鋼".to_string(),
+                volume: 0,
+                transactions: 0,
+                value: 0,
+                price: Cell::NotApplicable,
+                last_best_bid_price: Cell::NotApplicable,
+                last_best_ask_price: Cell::Value(dec!(61.2)),
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].stock_id);
+            assert_eq!(d.stock_name, wants[n].stock_name);
+            assert_eq!(d.volume, wants[n].volume);
+            assert_eq!(d.transactions, wants[n].transactions);
+            assert_eq!(d.value, wants[n].value);
+            assert_eq!(d.price, wants[n].price);
+            assert_eq!(d.last_best_bid_price, wants[n].last_best_bid_price);
+            assert_eq!(d.last_best_ask_price, wants[n].last_best_ask_price);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_market_daily_summary() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210201",
+            "title":"110年02月市場成交資訊",
+            "fields":["日期","成交股數","成交金額","成交筆數","發行量加權股價指數","漲跌點數"],
+            "data":[
+                ["110/02/01","6,823,443,617","307,126,485,283","2,021,594","15,410.09","271.60"],
+                ["110/02/02","6,410,870,521","298,418,337,446","1,858,111","15,760.05","349.96"],
+                ["110/02/05","5,645,915,064","286,296,431,474","1,690,022","15,802.37","-4.27"]
+            ],
+            "notes":["當日統計資訊含一般、零股、盤後定價、鉅額交易，不含拍賣、標購。"]
+        }
+        "#;
+        let result: ReportResponse<MarketDailySummary> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 3);
+
+        let wants = [
+            MarketDailySummary {
+                date: NaiveDate::from_ymd_opt(2021, 2, 1).unwrap(),
+                volume: 6823443617,
+                value: 307126485283,
+                transactions: 2021594,
+                taiex: dec!(15410.09),
+                change: dec!(271.6),
+            },
+            MarketDailySummary {
+                date: NaiveDate::from_ymd_opt(2021, 2, 2).unwrap(),
+                volume: 6410870521,
+                value: 298418337446,
+                transactions: 1858111,
+                taiex: dec!(15760.05),
+                change: dec!(349.96),
+            },
+            MarketDailySummary {
+                date: NaiveDate::from_ymd_opt(2021, 2, 5).unwrap(),
+                volume: 5645915064,
+                value: 286296431474,
+                transactions: 1690022,
+                taiex: dec!(15802.37),
+                change: dec!(-4.27),
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.date, wants[n].date);
+            assert_eq!(d.volume, wants[n].volume);
+            assert_eq!(d.value, wants[n].value);
+            assert_eq!(d.transactions, wants[n].transactions);
+            assert_eq!(d.taiex, wants[n].taiex);
+            assert_eq!(d.change, wants[n].change);
+        }
+
+        let snapshots = DailyMarketSnapshot::join(
+            wants.to_vec(),
+            vec![(
+                NaiveDate::from_ymd_opt(2021, 2, 5).unwrap(),
+                vec![TotalInstitutionalInvestors {
+                    name: "合計".to_string(),
+                    name_en: None,
+                    buy: 87532849369,
+                    sell: 89714793209,
+                    difference: -2181943840,
+                }],
+            )],
+        );
+        assert_eq!(snapshots.len(), 3);
+        assert!(snapshots[0].institutional_investors.is_none());
+        assert!(snapshots[1].institutional_investors.is_none());
+        assert_eq!(
+            snapshots[2].date,
+            NaiveDate::from_ymd_opt(2021, 2, 5).unwrap()
+        );
+        assert_eq!(snapshots[2].summary.taiex, dec!(15802.37));
+        if let Some(investors) = &snapshots[2].institutional_investors {
+            assert_eq!(investors.len(), 1);
+            assert_eq!(investors[0].difference, -2181943840);
+        } else {
+            assert!(false, "snapshot of 2021-02-05 should be joined");
+        }
+
+        let json = serde_json::to_value(&snapshots[2])?;
+        assert_eq!(json["date"], "2021-02-05");
+        assert_eq!(json["institutional_investors_1_difference"], -2181943840i64);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_intraday_market_statistics() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 每5秒委託成交統計",
+            "fields":["時間","累積委託買進筆數","累積委託買進數量","累積委託賣出筆數","累積委託賣出數量","累積成交筆數","累積成交數量","累積成交金額"],
+            "data":[
+                ["09:00:00","173,463","2,016,862","167,041","1,465,914","0","0","0"],
+                ["09:00:05","182,106","2,107,744","177,536","1,580,208","34,913","285,338","14,022"]
+            ],
+            "notes":["單位：成交量(交易單位)、成交金額(百萬元)。"]
+        }
+        "#;
+        let result: ReportResponse<IntradayMarketStatistics> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            IntradayMarketStatistics {
+                time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                accumulated_buy_orders: 173463,
+                accumulated_buy_volume: 2016862,
+                accumulated_sell_orders: 167041,
+                accumulated_sell_volume: 1465914,
+                accumulated_transactions: 0,
+                accumulated_trade_volume: 0,
+                accumulated_trade_value: 0,
+            },
+            IntradayMarketStatistics {
+                time: NaiveTime::from_hms_opt(9, 0, 5).unwrap(),
+                accumulated_buy_orders: 182106,
+                accumulated_buy_volume: 2107744,
+                accumulated_sell_orders: 177536,
+                accumulated_sell_volume: 1580208,
+                accumulated_transactions: 34913,
+                accumulated_trade_volume: 285338,
+                accumulated_trade_value: 14022,
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.time, wants[n].time);
+            assert_eq!(d.accumulated_buy_orders, wants[n].accumulated_buy_orders);
+            assert_eq!(d.accumulated_buy_volume, wants[n].accumulated_buy_volume);
+            assert_eq!(d.accumulated_sell_orders, wants[n].accumulated_sell_orders);
+            assert_eq!(d.accumulated_sell_volume, wants[n].accumulated_sell_volume);
+            assert_eq!(
+                d.accumulated_transactions,
+                wants[n].accumulated_transactions
+            );
+            assert_eq!(
+                d.accumulated_trade_volume,
+                wants[n].accumulated_trade_volume
+            );
+            assert_eq!(d.accumulated_trade_value, wants[n].accumulated_trade_value);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_taiex_history() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210201",
+            "title":"110年02月 發行量加權股價指數歷史資料",
+            "fields":["日期","開盤指數","最高指數","最低指數","收盤指數"],
+            "data":[
+                ["110/02/01","15,183.48","15,416.65","15,152.39","15,410.09"],
+                ["110/02/02","15,547.10","15,760.05","15,519.50","15,760.05"]
+            ],
+            "notes":[]
+        }
+        "#;
+        let result: ReportResponse<TaiexHistory> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            TaiexHistory {
+                date: NaiveDate::from_ymd_opt(2021, 2, 1).unwrap(),
+                open: dec!(15183.48),
+                high: dec!(15416.65),
+                low: dec!(15152.39),
+                close: dec!(15410.09),
+            },
+            TaiexHistory {
+                date: NaiveDate::from_ymd_opt(2021, 2, 2).unwrap(),
+                open: dec!(15547.1),
+                high: dec!(15760.05),
+                low: dec!(15519.5),
+                close: dec!(15760.05),
+            },
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.date, wants[n].date);
+            assert_eq!(d.open, wants[n].open);
+            assert_eq!(d.high, wants[n].high);
+            assert_eq!(d.low, wants[n].low);
+            assert_eq!(d.close, wants[n].close);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_daily_quote_response() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 每日收盤行情(全部)",
+            "fields":["證券代號","證券名稱","成交股數","成交金額","開盤價","最高價","最低價","收盤價","漲跌價差","成交筆數"],
+            "data":[
+                ["2330","台積電","41,542,349","24,580,612,153","597.00","598.00","584.00","591.00","-2.00","48,576"],
+                ["1475","業旺","0","0","--","--","--","--","0.00","0"]
+            ]
+        }
+        "#;
+        let result: DailyQuoteResponse = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+        assert_eq!(result.data[0].stock_id(), "2330");
+        assert_eq!(result.data[0].volume, 41542349);
+        assert_eq!(result.data[0].close(), Some(dec!(591)));
+        assert_eq!(result.data[0].change, Cell::Value(dec!(-2)));
+        assert_eq!(result.data[1].open, Cell::NotApplicable);
+        assert_eq!(result.data[1].close(), None);
+
+        // the OpenAPI names the fields
+        let openapi = r#"
+        [{"Code":"2330","Name":"台積電","TradeVolume":"41542349","TradeValue":"24580612153","OpeningPrice":"597.0000","HighestPrice":"598.0000","LowestPrice":"584.0000","ClosingPrice":"591.0000","Change":"-2.0000","Transaction":"48576"}]
+        "#;
+        let rows: Vec<DailyQuote> = serde_json::from_str(openapi)?;
+        assert_eq!(rows[0].stock_name, "台積電");
+        assert_eq!(rows[0].value, 24580612153);
+        assert_eq!(rows[0].close(), Some(dec!(591)));
+        assert_eq!(rows[0].transactions, 48576);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_foreign_holding_response() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 外資及陸資投資持股統計",
+            "fields":["證券代號","證券名稱","國際證券編碼","發行股數","外資及陸資尚可投資股數","全體外資及陸資持有股數","外資及陸資尚可投資比率","全體外資及陸資持股比率","外資及陸資共用法令投資上限比率","陸資法令投資上限比率","與前日異動原因(註)","最近一次上市公司申報外資持股異動日期"],
+            "data":[
+                ["2330","台積電","TW0002330008","25,930,380,458","11,482,637,637","14,447,742,821","44.28","55.71","100.00","--","","110/02/04"]
+            ]
+        }
+        "#;
+        let result: ForeignHoldingResponse = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 1);
+        let d = &result.data[0];
+        assert_eq!(d.stock_id(), "2330");
+        assert_eq!(d.isin, "TW0002330008");
+        assert_eq!(d.shares_held, 14447742821);
+        assert_eq!(d.shares_held_percentage(), dec!(55.71));
+        assert_eq!(d.upper_limit_percentage, dec!(100));
+        assert_eq!(d.mainland_upper_limit_percentage, Cell::NotApplicable);
+
+        let openapi = r#"
+        [{"Code":"2330","Name":"台積電","ISINCode":"TW0002330008","IssuedShares":"25930380458","AvailableShares":"11482637637","SharesHeld":"14447742821","AvailableInvestPer":"44.28","SharesHeldPer":"55.71","Upperlimit":"100.00","ChineseUpperlimit":"--"}]
+        "#;
+        let rows: Vec<ForeignHolding> = serde_json::from_str(openapi)?;
+        assert_eq!(rows[0].issued_shares, 25930380458);
+        assert_eq!(rows[0].shares_held_percentage(), dec!(55.71));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sector_index_response() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "subtitle1":"110年02月05日 價格指數(臺灣證券交易所)",
+            "fields1":["指數","收盤指數","漲跌(+/-)","漲跌點數","漲跌百分比(%)","特殊處理註記"],
+            "data1":[
+                ["寶島股價指數","17,720.51","<p style ='color:red'>+</p>","13.36","0.08",""],
+                ["發行量加權股價指數","15,802.37","<p style ='color:green'>-</p>","4.27","-0.03",""],
+                ["半導體類指數","355.12","<p style ='color:green'>-</p>","1.95","-0.55",""],
+                ["水泥類指數","166.95"," ","0.00","0.00",""]
+            ]
+        }
+        "#;
+        let result: SectorIndexResponse = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data1.len(), 4);
+
+        let wants = [
+            (None, dec!(17720.51), PriceDirection::Up, dec!(13.36)),
+            (
+                Some(Index::Taiex),
+                dec!(15802.37),
+                PriceDirection::Down,
+                dec!(-4.27),
+            ),
+            (
+                Some(Index::Semiconductor),
+                dec!(355.12),
+                PriceDirection::Down,
+                dec!(-1.95),
+            ),
+            (
+                Some(Index::Cement),
+                dec!(166.95),
+                PriceDirection::Flat,
+                dec!(0.0),
+            ),
+        ];
+
+        for n in 0..result.data1.len() {
+            let d = &result.data1[n];
+            assert_eq!(d.index(), wants[n].0);
+            assert_eq!(d.close, wants[n].1);
+            assert_eq!(d.direction, wants[n].2);
+            assert_eq!(d.change(), wants[n].3);
+        }
+
+        assert_eq!(
+            Index::from_industry_type(crate::args::IndustryType::Semiconductor),
+            Some(Index::Semiconductor)
+        );
+        assert_eq!(Index::Semiconductor.value(), "半導體類指數");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_roc_date() {
+        let test_cases = vec![
+            ("110/02/05", NaiveDate::from_ymd_opt(2021, 2, 5)),
+            ("110年02月05日", NaiveDate::from_ymd_opt(2021, 2, 5)),
+            ("109/02/29", NaiveDate::from_ymd_opt(2020, 2, 29)),
+            ("1100205", NaiveDate::from_ymd_opt(2021, 2, 5)),
+            ("990205", NaiveDate::from_ymd_opt(2010, 2, 5)),
+            ("1100229", None),
+            ("110/02/29", None),
+            ("110/02", None),
+            ("110/02/05/01", None),
+            ("", None),
+        ];
+
+        for (input, want) in test_cases {
+            assert_eq!(roc_date(input), want, "input:{}", input);
+        }
+    }
+
+    #[test]
+    fn test_response_language() -> Result<()> {
+        let zh = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 三大法人買賣金額統計表",
+            "fields":["單位名稱","買進金額","賣出金額","買賣差額"],
+            "data":[["外資及陸資","73,576,875,154","79,202,203,729","-5,625,328,575"]]
+        }
+        "#;
+        let en = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"2021/02/05 Trading Value of Foreign & Other Investors",
+            "fields":["Item","Total Buy","Total Sell","Difference"],
+            "data":[["Foreign Investors include Mainland Area Investors  ","73,576,875,154","79,202,203,729","-5,625,328,575"]]
+        }
+        "#;
+        let zh: Response = serde_json::from_str(zh)?;
+        let en: Response = serde_json::from_str(en)?;
+
+        assert_eq!(
+            zh.language(&TOTAL_INSTITUTIONAL_INVESTORS_HEADERS)?,
+            Language::Chinese
+        );
+        assert_eq!(
+            en.language(&TOTAL_INSTITUTIONAL_INVESTORS_HEADERS)?,
+            Language::English
+        );
+        assert!(matches!(
+            zh.language(&SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS),
+            Err(TwseError::FieldsError(_))
+        ));
+
+        let to_rows = |response: Response| -> Vec<TotalInstitutionalInvestors> {
+            response
+                .data
+                .into_iter()
+                .filter_map(|data| match data {
+                    Data::TotalInstitutionalInvestors(d) => Some(d),
+                    _ => None,
+                })
+                .collect()
+        };
+        let rows = TotalInstitutionalInvestors::with_english_names(to_rows(zh), to_rows(en));
+        assert_eq!(rows[0].name, "外資及陸資");
+        assert_eq!(
+            rows[0].name_en.as_deref(),
+            Some("Foreign Investors include Mainland Area Investors")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_roc_date() {
+        let date = NaiveDate::from_ymd_opt(2021, 2, 5).unwrap();
+        assert_eq!(format_roc_date(date), "110/02/05");
+        assert_eq!(roc_date(&format_roc_date(date)), Some(date));
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        let test_cases = [
+            ("台泥            ", "台泥", "trailing spaces"),
+            ("TAIWAN  CEMENT", "TAIWAN CEMENT", "inner spaces"),
+            ("\u{3000}台積電\u{3000}", "台積電", "ideographic spaces"),
+            ("\u{a0}TSMC\u{a0}\u{a0}ADR ", "TSMC ADR", "no-break spaces"),
+            ("元大台灣50\t\r\n", "元大台灣50", "control whitespace"),
+            ("", "", "empty"),
+        ];
+
+        for (input, want, description) in test_cases.iter() {
+            assert_eq!(normalize_name(input), *want, "[{}]", description);
+        }
+    }
+
+    #[cfg(feature = "nfkc")]
+    #[test]
+    fn test_normalize_name_nfkc() {
+        assert_eq!(normalize_name("臻鼎－ＫＹ"), "臻鼎-KY");
+        assert_eq!(normalize_name("元大台灣５０\u{3000}"), "元大台灣50");
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        let test_cases = [
+            ("1,415.00", Some(dec!(1415.00)), "thousands separators"),
+            ("+0.50", Some(dec!(0.50)), "plus sign"),
+            ("-4.27", Some(dec!(-4.27)), "minus sign"),
+            (
+                "<p style= color:red>+13.36</p>",
+                Some(dec!(13.36)),
+                "colored",
+            ),
+            (" 72.31 ", Some(dec!(72.31)), "padded"),
+            ("--", None, "no trades"),
+            ("X", None, "not comparable"),
+            ("", None, "empty"),
+        ];
+
+        for (input, want, description) in test_cases.iter() {
+            let deserializer = StrDeserializer::<ValueError>::new(input);
+            let got = parse_optional_decimal(deserializer);
+            assert_eq!(got.ok(), Some(*want), "[{}]: input:{}", description, input);
+
+            let deserializer = StrDeserializer::<ValueError>::new(input);
+            assert_eq!(parse_decimal(deserializer).ok(), *want, "[{}]", description);
+        }
+
+        let deserializer = StrDeserializer::<ValueError>::new("1.2.3");
+        assert!(parse_optional_decimal(deserializer).is_err());
+    }
+
+    #[test]
+    fn test_tpex_response_with_single_stock_institutional_investors() -> Result<()> {
+        let response = r#"
+        {
+            "reportDate":"110/02/05",
+            "iTotalRecords":2,
+            "aaData":[
+                ["6488","環球晶            ","1,316,473","1,079,050","237,423","0","0","0","1,316,473","1,079,050","237,423","60,000","2,000","58,000","12,000","29,000","-17,000","3,000","1,000","2,000","15,000","30,000","-15,000","280,423"],
+                ["8299","群聯            ","466,000","1,047,233","-581,233","0","1,000","-1,000","466,000","1,048,233","-582,233","0","0","0","4,000","0","4,000","0","2,000","-2,000","4,000","2,000","2,000","-580,233"]
+            ]
+        }
+        "#;
+        let result: TpexResponse = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.date, NaiveDate::from_ymd_opt(2021, 2, 5));
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            ("6488", "環球晶", 237423, 0, 58000, -17000, -15000, 280423),
+            ("8299", "群聯", -581233, -1000, 0, 4000, 2000, -580233),
+        ];
+
+        for (n, data) in result.data.into_iter().enumerate() {
+            if let TpexData::SingleStockInstitutionalInvestors(d) = data {
+                let d = d.into_single_stock();
+                assert_eq!(d.stock_id, wants[n].0);
+                assert_eq!(d.stock_name, wants[n].1);
+                assert_eq!(d.foreign_investor_difference, wants[n].2);
+                assert_eq!(d.foreign_dealer_self_difference, wants[n].3);
+                assert_eq!(d.investment_trust_difference, wants[n].4);
+                assert_eq!(d.dealer_self_difference, wants[n].5);
+                assert_eq!(d.dealer_total_difference, wants[n].6);
+                assert_eq!(d.total_difference, wants[n].7);
+            } else {
+                assert!(false, "data vector index {} cannot cast out", n);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tpex_response_with_total_institutional_investors() -> Result<()> {
+        let response = r#"
+        {
+            "reportDate":"110/02/05",
+            "iTotalRecords":3,
+            "aaData":[
+                ["外資及陸資(不含外資自營商)","20,185,322,000","19,112,553,000","1,072,769,000"],
+                ["投信","1,011,025,000","612,004,000","399,021,000"],
+                ["自營商(自行買賣)","2,063,338,000","2,154,997,000","-91,659,000"]
+            ]
+        }
+        "#;
+        let result: TpexResponse = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        let wants = [
+            (
+                "外資及陸資(不含外資自營商)",
+                20185322000,
+                19112553000,
+                1072769000,
+            ),
+            ("投信", 1011025000, 612004000, 399021000),
+            ("自營商(自行買賣)", 2063338000, 2154997000, -91659000),
+        ];
+
+        for (n, data) in result.data.iter().enumerate() {
+            if let TpexData::TotalInstitutionalInvestors(d) = data {
+                assert_eq!(d.name, wants[n].0);
+                assert_eq!(d.buy, wants[n].1);
+                assert_eq!(d.sell, wants[n].2);
+                assert_eq!(d.difference, wants[n].3);
+            } else {
+                assert!(false, "data vector index {} cannot cast out", n);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_ex_rights_schedule() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"除權除息預告表",
+            "fields":["除權除息日期","股票代號","名稱","除權息","無償配股率","現金增資配股率","現金增資認購價","現金股利","詳細資料","參考價試算","最近一次申報資料 季別/日期","最近一次申報每股 (單位)淨值","最近一次申報每股 (單位)盈餘"],
+            "data":[
+                ["110年03月17日","2330","台積電","息","0","0","0","2.50000000","<a href='#'>詳細資料</a>","<a href='#'>試算</a>","109年第3季","88.25","14.61"],
+                ["110年03月22日","2887","台新金","權息","0.03680000","0","0","0.37700000","<a href='#'>詳細資料</a>","<a href='#'>試算</a>","109年第3季","14.56","0.92"]
+            ],
+            "notes":[]
+        }
+        "#;
+        let result: ReportResponse<ExRightsSchedule> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            (
+                NaiveDate::from_ymd_opt(2021, 3, 17).unwrap(),
+                "2330",
+                RightType::Dividend,
+                dec!(0.0),
+                dec!(2.5),
+                "109年第3季",
+            ),
+            (
+                NaiveDate::from_ymd_opt(2021, 3, 22).unwrap(),
+                "2887",
+                RightType::RightsAndDividend,
+                dec!(0.0368),
+                dec!(0.377),
+                "109年第3季",
+            ),
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.date, wants[n].0);
+            assert_eq!(d.stock_id, wants[n].1);
+            assert_eq!(d.right_type, wants[n].2);
+            assert_eq!(d.stock_dividend_ratio, wants[n].3);
+            assert_eq!(d.cash_dividend, wants[n].4);
+            assert_eq!(d.latest_report_period, wants[n].5);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_ex_rights_result() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年01月04日 至 110年02月05日 除權除息計算結果表",
+            "fields":["資料日期","股票代號","股票名稱","除權息前收盤價","除權息參考價","權值+息值","權/息","漲停價格","跌停價格","開盤競價基準","減除股利參考價","詳細資料","最近一次申報資料 季別/日期","最近一次申報每股 (單位)淨值","最近一次申報每股 (單位)盈餘"],
+            "data":[
+                ["110年01月14日","2330","台積電","601.00","598.50","2.5","息","658.00","539.00","598.50","598.50","<a href='#'>詳細資料</a>","109年第3季","88.25","14.61"],
+                ["110年01月20日","1409","新纖","13.95","13.56","0.39","權","14.90","12.25","13.56","13.56","<a href='#'>詳細資料</a>","109年第3季","20.12","0.21"]
             ],
-            "selectType":"01",
-            "notes":[
-                "自營商表示證券自營商專戶。",
-                "投信表示本國投資信託基金。",
-                "外資及陸資表示依「華僑及外國人投資證券管理辦法」及「大陸地區投資人來臺從事證券投資及期貨交易管理辦法」辦理登記等投資人。",
-                "外資自營商買賣股數已計入自營商買賣股數，故不納入三大法人買賣股數之合計數計算。",
-                "本統計資訊含一般、零股、盤後定價、鉅額，不含拍賣、標購。",
-                "本資訊以當日原始成交情形統計，不以證券商申報錯帳、更正帳號等調整後資料統計。",
-                "ETF證券代號第六碼為K、M、S、C者，表示該ETF以外幣交易。"
+            "notes":[]
+        }
+        "#;
+        let result: ReportResponse<ExRightsResult> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            (
+                NaiveDate::from_ymd_opt(2021, 1, 14).unwrap(),
+                "2330",
+                dec!(601.0),
+                dec!(598.5),
+                RightType::Dividend,
+                Some(dec!(2.5)),
+            ),
+            (
+                NaiveDate::from_ymd_opt(2021, 1, 20).unwrap(),
+                "1409",
+                dec!(13.95),
+                dec!(13.56),
+                RightType::Rights,
+                Some(dec!(0.0)),
+            ),
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.date, wants[n].0);
+            assert_eq!(d.stock_id, wants[n].1);
+            assert_eq!(d.previous_close, wants[n].2);
+            assert_eq!(d.reference_price, wants[n].3);
+            assert_eq!(d.right_type, wants[n].4);
+            assert_eq!(d.cash_dividend(), wants[n].5);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_corporate_events() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"減資恢復買賣參考價格",
+            "fields":["恢復買賣日期","股票代號","名稱","停止買賣前收盤價格","恢復買賣參考價","漲停價格","跌停價格","開始交易基準價","除權參考價","減資原因","詳細資料"],
+            "data":[
+                ["110/01/18","2888","新光金","9.43","10.59","11.65","9.54","10.59","10.59","彌補虧損","<a href='#'>詳細資料</a>"]
             ]
         }
         "#;
-        let result: Response = serde_json::from_str(response)
+        let result: ReportResponse<CapitalReduction> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+        assert_eq!(result.data.len(), 1);
+        let d = &result.data[0];
+        assert_eq!(d.resume_date, NaiveDate::from_ymd_opt(2021, 1, 18).unwrap());
+        assert_eq!(d.stock_id, "2888");
+        assert_eq!(d.last_close, dec!(9.43));
+        assert_eq!(d.reference_price, dec!(10.59));
+        assert_eq!(d.reason, "彌補虧損");
+
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"暫停交易及恢復交易",
+            "fields":["證券代號","證券名稱","暫停交易日期","暫停交易時間","恢復交易日期","恢復交易時間"],
+            "data":[
+                ["1337","再生-KY","110/01/28","09:00:00","110/02/04","09:00:00"],
+                ["2936","客思達-KY","110/02/03","09:00:00","",""]
+            ]
+        }
+        "#;
+        let result: ReportResponse<TradingSuspension> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+        assert_eq!(result.data.len(), 2);
+        let wants = [
+            (
+                "1337",
+                NaiveDate::from_ymd_opt(2021, 1, 28).unwrap(),
+                NaiveDate::from_ymd_opt(2021, 2, 4),
+            ),
+            ("2936", NaiveDate::from_ymd_opt(2021, 2, 3).unwrap(), None),
+        ];
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.stock_id, wants[n].0);
+            assert_eq!(d.suspend_date, wants[n].1);
+            assert_eq!(d.resume_date, wants[n].2);
+        }
+
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"變更面額恢復買賣參考價格",
+            "fields":["恢復買賣日期","股票代號","名稱","停止買賣前收盤價格","恢復買賣參考價","漲停價格","跌停價格","開始交易基準價","詳細資料"],
+            "data":[
+                ["110/01/25","6531","愛普","1,415.00","1,415.00","1,555.00","1,275.00","1,415.00","<a href='#'>詳細資料</a>"]
+            ]
+        }
+        "#;
+        let result: ReportResponse<ParValueChange> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+        assert_eq!(result.data.len(), 1);
+        let d = &result.data[0];
+        assert_eq!(d.resume_date, NaiveDate::from_ymd_opt(2021, 1, 25).unwrap());
+        assert_eq!(d.stock_id, "6531");
+        assert_eq!(d.last_close, dec!(1415.0));
+        assert_eq!(d.limit_up, dec!(1555.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_attention_stock() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月01日至110年02月05日 公布注意累計次數異常資訊",
+            "fields":["編號","證券代號","證券名稱","累計次數","注意交易資訊","日期","收盤價","本益比"],
+            "data":[
+                ["1","2409","友達","1","第一款<br>最近六個營業日累積收盤價漲幅達32.14%","110/02/05","17.85","22.87"],
+                ["2","00650L","復華香港正2","3","第十款","110/02/05","27.10","--"]
+            ]
+        }
+        "#;
+        let result: ReportResponse<AttentionStock> = serde_json::from_str(response)
             .with_context(|| format!("serde_json::from_str failed"))?;
 
         assert_eq!(result.stat, "OK");
-        assert_eq!(result.data.len(), 4);
+        assert_eq!(result.data.len(), 2);
 
         let wants = [
-            SingleStockInstitutionalInvestors {
-                stock_id: "1104".to_string(),
-                stock_name: "環泥".to_string(),
-                foreign_investor_buy: 121000,
-                foreign_investor_sell: 86000,
-                foreign_investor_difference: 35000,
-                foreign_dealer_self_buy: 0,
-                foreign_dealer_self_sell: 0,
-                foreign_dealer_self_difference: 0,
-                investment_trust_buy: 0,
-                investment_trust_sell: 0,
-                investment_trust_difference: 0,
-                dealer_total_difference: 3000,
-                dealer_self_buy: 3000,
-                dealer_self_sell: 0,
-                dealer_self_difference: 3000,
-                dealer_hedging_buy: 0,
-                dealer_hedging_sell: 0,
-                dealer_difference: 0,
-                total_difference: 38000,
-            },
-            SingleStockInstitutionalInvestors {
-                stock_id: "1108".to_string(),
-                stock_name: "幸福".to_string(),
-                foreign_investor_buy: 46000,
-                foreign_investor_sell: 30000,
-                foreign_investor_difference: 16000,
-                foreign_dealer_self_buy: 0,
-                foreign_dealer_self_sell: 0,
-                foreign_dealer_self_difference: 0,
-                investment_trust_buy: 0,
-                investment_trust_sell: 0,
-                investment_trust_difference: 0,
-                dealer_total_difference: 2000,
-                dealer_self_buy: 2000,
-                dealer_self_sell: 0,
-                dealer_self_difference: 2000,
-                dealer_hedging_buy: 0,
-                dealer_hedging_sell: 0,
-                dealer_difference: 0,
-                total_difference: 18000,
-            },
-            SingleStockInstitutionalInvestors {
-                stock_id: "1109".to_string(),
-                stock_name: "信大".to_string(),
-                foreign_investor_buy: 32000,
-                foreign_investor_sell: 25000,
-                foreign_investor_difference: 7000,
-                foreign_dealer_self_buy: 0,
-                foreign_dealer_self_sell: 0,
-                foreign_dealer_self_difference: 0,
-                investment_trust_buy: 0,
-                investment_trust_sell: 0,
-                investment_trust_difference: 0,
-                dealer_total_difference: 0,
-                dealer_self_buy: 0,
-                dealer_self_sell: 0,
-                dealer_self_difference: 0,
-                dealer_hedging_buy: 0,
-                dealer_hedging_sell: 0,
-                dealer_difference: 0,
-                total_difference: 7000,
-            },
-            SingleStockInstitutionalInvestors {
-                stock_id: "1101B".to_string(),
-                stock_name: "台泥乙特".to_string(),
-                foreign_investor_buy: 0,
-                foreign_investor_sell: 0,
-                foreign_investor_difference: 0,
-                foreign_dealer_self_buy: 0,
-                foreign_dealer_self_sell: 0,
-                foreign_dealer_self_difference: 0,
-                investment_trust_buy: 0,
-                investment_trust_sell: 0,
-                investment_trust_difference: 0,
-                dealer_total_difference: 1000,
-                dealer_self_buy: 1000,
-                dealer_self_sell: 0,
-                dealer_self_difference: 1000,
-                dealer_hedging_buy: 0,
-                dealer_hedging_sell: 0,
-                dealer_difference: 0,
-                total_difference: 1000,
-            },
-            SingleStockInstitutionalInvestors {
-                stock_id: "1110".to_string(),
-                stock_name: "東泥".to_string(),
-                foreign_investor_buy: 28000,
-                foreign_investor_sell: 31000,
-                foreign_investor_difference: -3000,
-                foreign_dealer_self_buy: 0,
-                foreign_dealer_self_sell: 0,
-                foreign_dealer_self_difference: 0,
-                investment_trust_buy: 0,
-                investment_trust_sell: 0,
-                investment_trust_difference: 0,
-                dealer_total_difference: 0,
-                dealer_self_buy: 0,
-                dealer_self_sell: 0,
-                dealer_self_difference: 0,
-                dealer_hedging_buy: 0,
-                dealer_hedging_sell: 0,
-                dealer_difference: 0,
-                total_difference: -3000,
-            },
+            (
+                "2409",
+                1,
+                vec![
+                    "第一款".to_string(),
+                    "最近六個營業日累積收盤價漲幅達32.14%".to_string(),
+                ],
+                Some(dec!(17.85)),
+                Cell::Value(dec!(22.87)),
+            ),
+            (
+                "00650L",
+                3,
+                vec!["第十款".to_string()],
+                Some(dec!(27.1)),
+                Cell::NotApplicable,
+            ),
         ];
 
-        for n in 0..result.data.len() {
-            if let Data::SingleStockInstitutionalInvestors(d) = &result.data[n] {
-                assert_eq!(d.stock_id, wants[n].stock_id);
-                assert_eq!(d.stock_name, wants[n].stock_name);
-                assert_eq!(d.foreign_investor_buy, wants[n].foreign_investor_buy);
-                assert_eq!(d.foreign_investor_sell, wants[n].foreign_investor_sell);
-                assert_eq!(
-                    d.foreign_investor_difference,
-                    wants[n].foreign_investor_difference
-                );
-                assert_eq!(d.foreign_dealer_self_buy, wants[n].foreign_dealer_self_buy);
-                assert_eq!(
-                    d.foreign_dealer_self_sell,
-                    wants[n].foreign_dealer_self_sell
-                );
-                assert_eq!(
-                    d.foreign_dealer_self_difference,
-                    wants[n].foreign_dealer_self_difference
-                );
-                assert_eq!(d.investment_trust_buy, wants[n].investment_trust_buy);
-                assert_eq!(d.investment_trust_sell, wants[n].investment_trust_sell);
-                assert_eq!(
-                    d.investment_trust_difference,
-                    wants[n].investment_trust_difference
-                );
-                assert_eq!(d.dealer_total_difference, wants[n].dealer_total_difference);
-                assert_eq!(d.dealer_self_buy, wants[n].dealer_self_buy);
-                assert_eq!(d.dealer_self_sell, wants[n].dealer_self_sell);
-                assert_eq!(d.dealer_self_difference, wants[n].dealer_self_difference);
-                assert_eq!(d.dealer_hedging_buy, wants[n].dealer_hedging_buy);
-                assert_eq!(d.dealer_hedging_sell, wants[n].dealer_hedging_sell);
-                assert_eq!(d.dealer_difference, wants[n].dealer_difference);
-                assert_eq!(d.total_difference, wants[n].total_difference);
-            } else {
-                assert!(false, "data vector index {} cannot cast out", n);
-            }
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(d.number, n as u64 + 1);
+            assert_eq!(d.stock_id, wants[n].0);
+            assert_eq!(d.cumulative_count, wants[n].1);
+            assert_eq!(d.reasons, wants[n].2);
+            assert_eq!(d.date, NaiveDate::from_ymd_opt(2021, 2, 5).unwrap());
+            assert_eq!(d.close, wants[n].3);
+            assert_eq!(d.price_earnings_ratio, wants[n].4);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_with_disposition_stock() -> Result<()> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月01日至110年02月05日 公布處置股票資訊",
+            "fields":["編號","公布日期","證券代號","證券名稱","累計","處置條件","處置起迄時間","處置措施","處置內容","備註"],
+            "data":[
+                ["1","110/02/05","2409","友達","1","連續三次","110/02/08~110/02/22","第一次處置","以人工管制之撮合終端機執行撮合作業(約每五分鐘撮合一次)",""],
+                ["2","110/02/05","3051","力特","2","第一款<br>第二款","110/02/08～110/02/22","第二次處置","以人工管制之撮合終端機執行撮合作業(約每二十分鐘撮合一次)",""]
+            ]
+        }
+        "#;
+        let result: ReportResponse<DispositionStock> = serde_json::from_str(response)
+            .with_context(|| format!("serde_json::from_str failed"))?;
+
+        assert_eq!(result.stat, "OK");
+        assert_eq!(result.data.len(), 2);
+
+        let wants = [
+            (
+                "2409",
+                vec!["連續三次".to_string()],
+                DispositionMeasure::First,
+                MatchingInterval::FiveMinutes,
+            ),
+            (
+                "3051",
+                vec!["第一款".to_string(), "第二款".to_string()],
+                DispositionMeasure::Second,
+                MatchingInterval::TwentyMinutes,
+            ),
+        ];
+
+        for (n, d) in result.data.iter().enumerate() {
+            assert_eq!(
+                d.announced_date,
+                NaiveDate::from_ymd_opt(2021, 2, 5).unwrap()
+            );
+            assert_eq!(d.stock_id, wants[n].0);
+            assert_eq!(d.reasons, wants[n].1);
+            assert_eq!(
+                d.period,
+                (
+                    NaiveDate::from_ymd_opt(2021, 2, 8).unwrap(),
+                    NaiveDate::from_ymd_opt(2021, 2, 22).unwrap()
+                )
+            );
+            assert_eq!(d.measure, wants[n].2);
+            assert_eq!(d.matching_interval(), wants[n].3);
         }
 
         Ok(())
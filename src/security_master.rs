@@ -0,0 +1,432 @@
+use crate::args::{IndustryType, Market, TpexIndustryType};
+use crate::encoding;
+use crate::errors::TwseError;
+use crate::schema::normalize_name;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const ISIN_LINK: &str = "https://isin.twse.com.tw/isin/C_public.jsp";
+const ISIN_TWSE_MODE: &str = "2";
+const ISIN_TPEX_MODE: &str = "4";
+const ISIN_DATE_FORMAT: &str = "%Y/%m/%d";
+// the ISIN directory separates the code and the name with an ideographic space
+const ISIN_CODE_NAME_SEPARATOR: char = '\u{3000}';
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecurityType {
+    Stock,
+    PreferredStock,
+    Warrant,
+    ETF,
+    ETN,
+    DepositoryReceipts,
+    BeneficialSecurities,
+    Other(String),
+}
+
+impl SecurityType {
+    // maps the section headers of the ISIN directory, e.g. 股票 or 上市認購(售)權證
+    fn from_name(name: &str) -> SecurityType {
+        match name {
+            "股票" => SecurityType::Stock,
+            "特別股" => SecurityType::PreferredStock,
+            "ETF" => SecurityType::ETF,
+            "ETN" => SecurityType::ETN,
+            "臺灣存託憑證(TDR)" => SecurityType::DepositoryReceipts,
+            _ if name.ends_with("認購(售)權證") => SecurityType::Warrant,
+            _ if name.starts_with("受益證券") => SecurityType::BeneficialSecurities,
+            _ => SecurityType::Other(name.to_string()),
+        }
+    }
+
+    fn value(&self) -> &str {
+        match self {
+            SecurityType::Stock => "股票",
+            SecurityType::PreferredStock => "特別股",
+            SecurityType::Warrant => "認購(售)權證",
+            SecurityType::ETF => "ETF",
+            SecurityType::ETN => "ETN",
+            SecurityType::DepositoryReceipts => "臺灣存託憑證(TDR)",
+            SecurityType::BeneficialSecurities => "受益證券",
+            SecurityType::Other(name) => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Security {
+    pub stock_id: String,
+    pub stock_name: String,
+    pub isin: String,
+    pub listing_date: NaiveDate,
+    pub market: Market,
+    pub industry_name: String,
+    pub industry_type: Option<IndustryType>,
+    // only set for TPEx securities, which have industries TWSE does not, e.g. 文化創意業
+    pub tpex_industry_type: Option<TpexIndustryType>,
+    pub cfi_code: String,
+    pub security_type: SecurityType,
+}
+
+#[derive(Debug, Default)]
+pub struct SecurityMaster {
+    securities: Vec<Security>,
+    by_id: HashMap<String, usize>,
+    by_name: HashMap<String, usize>,
+}
+
+impl SecurityMaster {
+    pub fn new(securities: Vec<Security>) -> SecurityMaster {
+        let mut by_id = HashMap::with_capacity(securities.len());
+        let mut by_name = HashMap::with_capacity(securities.len());
+        for (n, security) in securities.iter().enumerate() {
+            by_id.insert(security.stock_id.clone(), n);
+            by_name.insert(security.stock_name.clone(), n);
+        }
+
+        SecurityMaster {
+            securities,
+            by_id,
+            by_name,
+        }
+    }
+
+    // fetches both the 上市 and 上櫃 listings
+    pub fn fetch() -> Result<SecurityMaster, TwseError> {
+        let mut securities = fetch_securities(ISIN_TWSE_MODE)?;
+        securities.extend(fetch_securities(ISIN_TPEX_MODE)?);
+
+        Ok(SecurityMaster::new(securities))
+    }
+
+    // loads the cache file if it is younger than max_age, otherwise fetches and rewrites it
+    pub fn load_or_fetch(path: &Path, max_age: Duration) -> Result<SecurityMaster, TwseError> {
+        let fresh = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age <= max_age);
+        if fresh {
+            return SecurityMaster::load(path);
+        }
+
+        let master = SecurityMaster::fetch()?;
+        master.save(path)?;
+
+        Ok(master)
+    }
+
+    pub fn load(path: &Path) -> Result<SecurityMaster, TwseError> {
+        let content = fs::read_to_string(path).map_err(TwseError::CacheError)?;
+        let mut securities = vec![];
+        for line in content.lines().filter(|l| !l.is_empty()) {
+            securities.push(parse_cache_line(line).ok_or_else(|| {
+                TwseError::CacheError(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("invalid security master cache line {}", line),
+                ))
+            })?);
+        }
+
+        Ok(SecurityMaster::new(securities))
+    }
+
+    // one security per line with tab separated columns
+    pub fn save(&self, path: &Path) -> Result<(), TwseError> {
+        let mut content = String::new();
+        for s in &self.securities {
+            let market = match s.market {
+                Market::Twse => "上市",
+                Market::Tpex => "上櫃",
+            };
+            let columns = [
+                s.stock_id.as_str(),
+                s.stock_name.as_str(),
+                s.isin.as_str(),
+                &s.listing_date.format(ISIN_DATE_FORMAT).to_string(),
+                market,
+                s.industry_name.as_str(),
+                s.cfi_code.as_str(),
+                s.security_type.value(),
+            ]
+            .join("\t");
+            content.push_str(&columns);
+            content.push('\n');
+        }
+
+        fs::write(path, content).map_err(TwseError::CacheError)
+    }
+
+    pub fn by_id(&self, stock_id: &str) -> Option<&Security> {
+        self.by_id.get(stock_id).map(|&n| &self.securities[n])
+    }
+
+    pub fn by_name(&self, stock_name: &str) -> Option<&Security> {
+        self.by_name
+            .get(&normalize_name(stock_name))
+            .map(|&n| &self.securities[n])
+    }
+
+    pub fn securities(&self) -> &[Security] {
+        &self.securities
+    }
+}
+
+fn fetch_securities(mode: &str) -> Result<Vec<Security>, TwseError> {
+    let response = ureq::get(ISIN_LINK).query("strMode", mode).call()?;
+    let html = encoding::read_response(response)?;
+
+    Ok(parse_isin_html(&html))
+}
+
+fn parse_isin_html(html: &str) -> Vec<Security> {
+    let mut ret = vec![];
+    let mut security_type = SecurityType::Other(String::new());
+    for row in html.split("<tr").skip(1) {
+        let cells = parse_cells(row);
+        match cells.len() {
+            // section headers span the whole table, e.g. <td colspan=7><B> 股票<B></td>
+            1 => security_type = SecurityType::from_name(&cells[0]),
+            7 => {
+                if let Some(security) = parse_security(&cells, &security_type) {
+                    ret.push(security);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ret
+}
+
+fn parse_cells(row: &str) -> Vec<String> {
+    let row = row.split("</tr>").next().unwrap_or("");
+    row.split("<td")
+        .skip(1)
+        .map(|cell| {
+            let content = cell.split_once('>').map_or("", |(_, content)| content);
+            let content = content.split("</td>").next().unwrap_or("");
+            strip_tags(content).trim().to_string()
+        })
+        .collect()
+}
+
+fn strip_tags(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => ret.push(c),
+            _ => {}
+        }
+    }
+    ret
+}
+
+// the columns are 有價證券代號及名稱, 國際證券辨識號碼, 上市日, 市場別, 產業別, CFICode, 備註
+fn parse_security(cells: &[String], security_type: &SecurityType) -> Option<Security> {
+    let (stock_id, stock_name) = cells[0].split_once(ISIN_CODE_NAME_SEPARATOR)?;
+    let (stock_id, stock_name) = (stock_id.trim().to_string(), normalize_name(stock_name));
+    let listing_date = NaiveDate::parse_from_str(&cells[2], ISIN_DATE_FORMAT).ok()?;
+    let market = match cells[3].as_str() {
+        "上市" => Market::Twse,
+        "上櫃" => Market::Tpex,
+        _ => return None,
+    };
+
+    Some(Security {
+        stock_id,
+        stock_name,
+        isin: cells[1].clone(),
+        listing_date,
+        market,
+        industry_type: IndustryType::from_name(&cells[4]),
+        tpex_industry_type: match market {
+            Market::Twse => None,
+            Market::Tpex => TpexIndustryType::from_name(&cells[4]),
+        },
+        industry_name: normalize_name(&cells[4]),
+        cfi_code: cells[5].clone(),
+        security_type: security_type.clone(),
+    })
+}
+
+fn parse_cache_line(line: &str) -> Option<Security> {
+    let columns: Vec<&str> = line.split('\t').collect();
+    if columns.len() != 8 {
+        return None;
+    }
+
+    let cells = vec![
+        format!("{}{}{}", columns[0], ISIN_CODE_NAME_SEPARATOR, columns[1]),
+        columns[2].to_string(),
+        columns[3].to_string(),
+        columns[4].to_string(),
+        columns[5].to_string(),
+        columns[6].to_string(),
+        String::new(),
+    ];
+    parse_security(&cells, &SecurityType::from_name(columns[7]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISIN_HTML: &str = r#"
+<table class='h4' align=center cellSpacing=3 cellPadding=2 width=750 border=0>
+<tr align=center><td bgcolor=#D5FFD5>有價證券代號及名稱 </td><td bgcolor=#D5FFD5>國際證券辨識號碼(ISIN Code)</td><td bgcolor=#D5FFD5>上市日</td><td bgcolor=#D5FFD5>市場別</td><td bgcolor=#D5FFD5>產業別</td><td bgcolor=#D5FFD5>CFICode</td><td bgcolor=#D5FFD5>備註</td></tr>
+<tr><td bgcolor=#FAFAD2 colspan=7 ><B> 股票<B> </td></tr>
+<tr><td bgcolor=#FAFAD2>1101　台泥</td><td bgcolor=#FAFAD2>TW0001101004</td><td bgcolor=#FAFAD2>1962/02/09</td><td bgcolor=#FAFAD2>上市</td><td bgcolor=#FAFAD2>水泥工業</td><td bgcolor=#FAFAD2>ESVUFR</td><td bgcolor=#FAFAD2></td></tr>
+<tr><td bgcolor=#FAFAD2>2330　台積電</td><td bgcolor=#FAFAD2>TW0002330008</td><td bgcolor=#FAFAD2>1994/09/05</td><td bgcolor=#FAFAD2>上市</td><td bgcolor=#FAFAD2>半導體業</td><td bgcolor=#FAFAD2>ESVUFR</td><td bgcolor=#FAFAD2></td></tr>
+<tr><td bgcolor=#FAFAD2 colspan=7 ><B> ETF<B> </td></tr>
+<tr><td bgcolor=#FAFAD2>0050　元大台灣50</td><td bgcolor=#FAFAD2>TW0000050004</td><td bgcolor=#FAFAD2>2003/06/30</td><td bgcolor=#FAFAD2>上市</td><td bgcolor=#FAFAD2></td><td bgcolor=#FAFAD2>CEOGEU</td><td bgcolor=#FAFAD2></td></tr>
+</table>
+"#;
+
+    #[test]
+    fn test_parse_isin_html() {
+        let securities = parse_isin_html(ISIN_HTML);
+        assert_eq!(securities.len(), 3);
+
+        let wants = [
+            (
+                "1101",
+                "台泥",
+                "TW0001101004",
+                NaiveDate::from_ymd_opt(1962, 2, 9).unwrap(),
+                Some(IndustryType::Cement.value()),
+                SecurityType::Stock,
+            ),
+            (
+                "2330",
+                "台積電",
+                "TW0002330008",
+                NaiveDate::from_ymd_opt(1994, 9, 5).unwrap(),
+                Some(IndustryType::Semiconductor.value()),
+                SecurityType::Stock,
+            ),
+            (
+                "0050",
+                "元大台灣50",
+                "TW0000050004",
+                NaiveDate::from_ymd_opt(2003, 6, 30).unwrap(),
+                None,
+                SecurityType::ETF,
+            ),
+        ];
+
+        for n in 0..securities.len() {
+            let s = &securities[n];
+            assert_eq!(s.stock_id, wants[n].0);
+            assert_eq!(s.stock_name, wants[n].1);
+            assert_eq!(s.isin, wants[n].2);
+            assert_eq!(s.listing_date, wants[n].3);
+            assert_eq!(s.market, Market::Twse);
+            assert_eq!(s.industry_type.as_ref().map(|t| t.value()), wants[n].4);
+            assert!(s.tpex_industry_type.is_none());
+            assert_eq!(s.security_type, wants[n].5);
+        }
+    }
+
+    #[test]
+    fn test_parse_tpex_security() {
+        let test_cases = vec![
+            (
+                "文化創意業",
+                None,
+                Some(TpexIndustryType::CulturalCreative.value()),
+            ),
+            (
+                "半導體業",
+                Some(IndustryType::Semiconductor.value()),
+                Some(TpexIndustryType::Semiconductor.value()),
+            ),
+            ("", None, None),
+        ];
+
+        for (industry_name, want_industry, want_tpex_industry) in test_cases {
+            let cells: Vec<String> = vec![
+                "6488　環球晶",
+                "TW0006488000",
+                "2008/09/25",
+                "上櫃",
+                industry_name,
+                "ESVUFR",
+                "",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect();
+            let s = parse_security(&cells, &SecurityType::Stock).unwrap();
+            assert_eq!(s.market, Market::Tpex, "[{}]", industry_name);
+            assert_eq!(
+                s.industry_type.as_ref().map(|t| t.value()),
+                want_industry,
+                "[{}]",
+                industry_name
+            );
+            assert_eq!(
+                s.tpex_industry_type.as_ref().map(|t| t.value()),
+                want_tpex_industry,
+                "[{}]",
+                industry_name
+            );
+        }
+    }
+
+    #[test]
+    fn test_big5_isin_html() {
+        let (big5, _, had_errors) = encoding_rs::BIG5.encode(ISIN_HTML);
+        assert!(!had_errors);
+
+        let html = encoding::decode(&big5, None);
+        assert_eq!(parse_isin_html(&html).len(), 3);
+    }
+
+    #[test]
+    fn test_security_master_lookup_and_cache() {
+        let master = SecurityMaster::new(parse_isin_html(ISIN_HTML));
+        assert_eq!(
+            master.by_id("2330").map(|s| s.stock_name.as_str()),
+            Some("台積電")
+        );
+        assert_eq!(
+            master.by_name("台泥").map(|s| s.stock_id.as_str()),
+            Some("1101")
+        );
+        assert!(master.by_id("9999").is_none());
+
+        let path = std::env::temp_dir().join(format!(
+            "twse-rs-security-master-{}.tsv",
+            std::process::id()
+        ));
+        master.save(&path).unwrap();
+        let cached = SecurityMaster::load_or_fetch(&path, Duration::from_secs(60)).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(cached.securities().len(), master.securities().len());
+        for (got, want) in cached.securities().iter().zip(master.securities()) {
+            assert_eq!(got.stock_id, want.stock_id);
+            assert_eq!(got.stock_name, want.stock_name);
+            assert_eq!(got.isin, want.isin);
+            assert_eq!(got.listing_date, want.listing_date);
+            assert_eq!(got.market, want.market);
+            assert_eq!(got.industry_name, want.industry_name);
+            assert_eq!(got.cfi_code, want.cfi_code);
+            assert_eq!(got.security_type, want.security_type);
+        }
+    }
+
+    #[test]
+    fn test_fetch_security_master() {
+        let master = SecurityMaster::fetch();
+        assert!(master.is_ok(), "fetch should be ok");
+        assert!(master.unwrap().by_id("2330").is_some());
+    }
+}
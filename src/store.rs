@@ -0,0 +1,647 @@
+use crate::args::{
+    DateType, IndustryType, Market, SingleStockInstitutionalInvestorsArgs,
+    TotalInstitutionInvestorsArgs,
+};
+use crate::client::Client;
+use crate::errors::TwseError;
+use crate::schema::{SingleStockInstitutionalInvestors, TotalInstitutionalInvestors};
+use chrono::{FixedOffset, NaiveDate, Utc};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+// the reports are dated in Taiwan time
+const TAIPEI_OFFSET_SECONDS: i32 = 8 * 3600;
+
+const SINGLE_STOCK_INSTITUTIONAL: &str = "single_stock_institutional";
+const TOTAL_INSTITUTIONAL: &str = "total_institutional";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS single_stock_institutional (
+    date TEXT NOT NULL,
+    date_type TEXT NOT NULL,
+    stock_id TEXT NOT NULL,
+    stock_name TEXT NOT NULL,
+    foreign_investor_buy INTEGER NOT NULL,
+    foreign_investor_sell INTEGER NOT NULL,
+    foreign_investor_difference INTEGER NOT NULL,
+    foreign_dealer_self_buy INTEGER NOT NULL,
+    foreign_dealer_self_sell INTEGER NOT NULL,
+    foreign_dealer_self_difference INTEGER NOT NULL,
+    investment_trust_buy INTEGER NOT NULL,
+    investment_trust_sell INTEGER NOT NULL,
+    investment_trust_difference INTEGER NOT NULL,
+    dealer_total_difference INTEGER NOT NULL,
+    dealer_self_buy INTEGER NOT NULL,
+    dealer_self_sell INTEGER NOT NULL,
+    dealer_self_difference INTEGER NOT NULL,
+    dealer_hedging_buy INTEGER NOT NULL,
+    dealer_hedging_sell INTEGER NOT NULL,
+    dealer_difference INTEGER NOT NULL,
+    total_difference INTEGER NOT NULL,
+    stock_name_en TEXT,
+    PRIMARY KEY (date, date_type, stock_id)
+);
+CREATE TABLE IF NOT EXISTS total_institutional (
+    date TEXT NOT NULL,
+    date_type TEXT NOT NULL,
+    name TEXT NOT NULL,
+    buy INTEGER NOT NULL,
+    sell INTEGER NOT NULL,
+    difference INTEGER NOT NULL,
+    name_en TEXT,
+    PRIMARY KEY (date, date_type, name)
+);
+CREATE TABLE IF NOT EXISTS downloads (
+    endpoint TEXT NOT NULL,
+    date_type TEXT NOT NULL,
+    date TEXT NOT NULL,
+    industry TEXT NOT NULL,
+    row_count INTEGER NOT NULL,
+    downloaded_at TEXT NOT NULL,
+    PRIMARY KEY (endpoint, date_type, date, industry)
+);
+";
+
+// the TWSE endpoints the rows come from, with the industry they were asked for
+#[derive(Debug, Copy, Clone)]
+pub enum Endpoint {
+    // T86, TWT54U and TWT47U
+    SingleStockInstitutional(IndustryType),
+    // BFI82U
+    TotalInstitutional,
+}
+
+impl Endpoint {
+    pub fn value(&self) -> &str {
+        match self {
+            Endpoint::SingleStockInstitutional(_) => SINGLE_STOCK_INSTITUTIONAL,
+            Endpoint::TotalInstitutional => TOTAL_INSTITUTIONAL,
+        }
+    }
+
+    fn industry(&self) -> &str {
+        match self {
+            Endpoint::SingleStockInstitutional(industry_type) => industry_type.value(),
+            Endpoint::TotalInstitutional => "",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SyncReport {
+    // the reports fetched, days without data included
+    pub downloaded: usize,
+    // the reports already in the store
+    pub skipped: usize,
+    pub rows: usize,
+}
+
+// keeps the institutional investors of TWSE in a sqlite database,
+// the reports are keyed by their date, period and stock id or name
+pub struct Store {
+    conn: Connection,
+    client: Client,
+    date_type: DateType,
+    industry_type: IndustryType,
+}
+
+impl Store {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Store, TwseError> {
+        Store::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Store, TwseError> {
+        Store::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Store, TwseError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Store {
+            conn,
+            client: Client::default(),
+            date_type: DateType::Day,
+            industry_type: IndustryType::All,
+        })
+    }
+
+    // the client sync fetches with, e.g. one asking for English names too
+    pub fn client(mut self, client: Client) -> Store {
+        self.client = client;
+        self
+    }
+
+    // weeks are stored on their monday and months on their first day
+    pub fn date_type(mut self, date_type: DateType) -> Store {
+        self.date_type = date_type;
+        self
+    }
+
+    // the industry of the single stock reports sync fetches
+    pub fn industry_type(mut self, industry_type: IndustryType) -> Store {
+        self.industry_type = industry_type;
+        self
+    }
+
+    pub fn upsert_single_stock_institutional(
+        &mut self,
+        date: NaiveDate,
+        date_type: DateType,
+        rows: &[SingleStockInstitutionalInvestors],
+    ) -> Result<usize, TwseError> {
+        let tx = self.conn.transaction()?;
+        let count = upsert(
+            &tx,
+            SINGLE_STOCK_INSTITUTIONAL,
+            &["stock_id"],
+            date,
+            date_type,
+            rows,
+        )?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    pub fn upsert_total_institutional(
+        &mut self,
+        date: NaiveDate,
+        date_type: DateType,
+        rows: &[TotalInstitutionalInvestors],
+    ) -> Result<usize, TwseError> {
+        let tx = self.conn.transaction()?;
+        let count = upsert(&tx, TOTAL_INSTITUTIONAL, &["name"], date, date_type, rows)?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    pub fn is_downloaded(
+        &self,
+        endpoint: Endpoint,
+        date: NaiveDate,
+        date_type: DateType,
+    ) -> Result<bool, TwseError> {
+        let found = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM downloads WHERE endpoint = ?1 AND date_type = ?2 AND date = ?3 AND industry = ?4",
+                params![
+                    endpoint.value(),
+                    date_type.value(),
+                    date.format(DATE_FORMAT).to_string(),
+                    endpoint.industry()
+                ],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    // fetches the reports from..to which are not in the store yet
+    pub fn sync(&mut self, from: NaiveDate, to: NaiveDate) -> Result<SyncReport, TwseError> {
+        let client = self.client.clone();
+        let (date_type, industry_type) = (self.date_type, self.industry_type);
+        let today = Utc::now()
+            .with_timezone(&FixedOffset::east_opt(TAIPEI_OFFSET_SECONDS).unwrap())
+            .date_naive();
+
+        self.sync_with(
+            from,
+            to,
+            today,
+            |date| {
+                client.get_single_stock_institutional_investors(
+                    SingleStockInstitutionalInvestorsArgs {
+                        date,
+                        date_type,
+                        market: Market::Twse,
+                        industry_type,
+                    },
+                )
+            },
+            |date| {
+                client.get_total_institution_investors(TotalInstitutionInvestorsArgs {
+                    date,
+                    date_type,
+                    market: Market::Twse,
+                })
+            },
+        )
+    }
+
+    fn sync_with<S, T>(
+        &mut self,
+        from: NaiveDate,
+        to: NaiveDate,
+        today: NaiveDate,
+        get_single: S,
+        get_total: T,
+    ) -> Result<SyncReport, TwseError>
+    where
+        S: Fn(NaiveDate) -> Result<Vec<SingleStockInstitutionalInvestors>, TwseError>,
+        T: Fn(NaiveDate) -> Result<Vec<TotalInstitutionalInvestors>, TwseError>,
+    {
+        let mut report = SyncReport::default();
+        let single = Endpoint::SingleStockInstitutional(self.industry_type);
+        let total = Endpoint::TotalInstitutional;
+
+        for date in self.date_type.report_dates(from, to) {
+            if self.is_downloaded(single, date, self.date_type)? {
+                report.skipped += 1;
+            } else if let Some(rows) = self.fetch(single, date, today, &get_single)? {
+                report.downloaded += 1;
+                report.rows += rows;
+            }
+
+            if self.is_downloaded(total, date, self.date_type)? {
+                report.skipped += 1;
+            } else if let Some(rows) = self.fetch(total, date, today, &get_total)? {
+                report.downloaded += 1;
+                report.rows += rows;
+            }
+        }
+
+        Ok(report)
+    }
+
+    // None when the report is not out yet, so it is fetched again by the next sync
+    fn fetch<R, F>(
+        &mut self,
+        endpoint: Endpoint,
+        date: NaiveDate,
+        today: NaiveDate,
+        get: F,
+    ) -> Result<Option<usize>, TwseError>
+    where
+        R: Serialize,
+        F: Fn(NaiveDate) -> Result<Vec<R>, TwseError>,
+    {
+        let rows = match get(date) {
+            Ok(rows) => rows,
+            // holidays have no report, but today's may be published later
+            Err(e) if e.is_no_data() && date < today => vec![],
+            Err(e) if e.is_no_data() => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let tx = self.conn.transaction()?;
+        let (table, keys): (&str, &[&str]) = match endpoint {
+            Endpoint::SingleStockInstitutional(_) => (SINGLE_STOCK_INSTITUTIONAL, &["stock_id"]),
+            Endpoint::TotalInstitutional => (TOTAL_INSTITUTIONAL, &["name"]),
+        };
+        let count = upsert(&tx, table, keys, date, self.date_type, &rows)?;
+        mark_downloaded(&tx, endpoint, date, self.date_type, count)?;
+        tx.commit()?;
+
+        Ok(Some(count))
+    }
+}
+
+// the columns are the serialized fields of the rows, and a re-fetched row replaces
+// the stored one, except for the English names it was not fetched with
+fn upsert<R: Serialize>(
+    tx: &Transaction,
+    table: &str,
+    keys: &[&str],
+    date: NaiveDate,
+    date_type: DateType,
+    rows: &[R],
+) -> Result<usize, TwseError> {
+    let date = date.format(DATE_FORMAT).to_string();
+    for row in rows.iter() {
+        let fields = match serde_json::to_value(row)
+            .map_err(|e| TwseError::FieldsError(format!("{}: {}", table, e)))?
+        {
+            Value::Object(fields) => fields,
+            _ => return Err(TwseError::FieldsError(table.to_string())),
+        };
+
+        let mut columns = vec!["date", "date_type"];
+        let mut values = vec![
+            SqlValue::Text(date.clone()),
+            SqlValue::Text(date_type.value().to_string()),
+        ];
+        for (column, value) in fields.iter() {
+            columns.push(column);
+            values.push(sql_value(value));
+        }
+
+        let updates: Vec<String> = columns
+            .iter()
+            .skip(2)
+            .filter(|column| !keys.contains(column))
+            .map(|column| format!("{0} = coalesce(excluded.{0}, {0})", column))
+            .collect();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT (date, date_type, {}) DO UPDATE SET {}",
+            table,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", "),
+            keys.join(", "),
+            updates.join(", ")
+        );
+        tx.prepare_cached(&sql)?.execute(params_from_iter(values))?;
+    }
+
+    Ok(rows.len())
+}
+
+fn sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(n) => SqlValue::Integer(n),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        value => SqlValue::Text(value.to_string()),
+    }
+}
+
+fn mark_downloaded(
+    tx: &Transaction,
+    endpoint: Endpoint,
+    date: NaiveDate,
+    date_type: DateType,
+    rows: usize,
+) -> Result<(), TwseError> {
+    tx.execute(
+        "INSERT OR REPLACE INTO downloads (endpoint, date_type, date, industry, row_count, downloaded_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            endpoint.value(),
+            date_type.value(),
+            date.format(DATE_FORMAT).to_string(),
+            endpoint.industry(),
+            rows as i64,
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Data, Response};
+    use std::cell::Cell;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    fn total_rows() -> Vec<TotalInstitutionalInvestors> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 三大法人買賣金額統計表",
+            "fields":["單位名稱","買進金額","賣出金額","買賣差額"],
+            "data":[
+                ["自營商(自行買賣)","3,720,692,397","2,657,469,622","1,063,222,775"],
+                ["合計","87,532,849,369","89,714,793,209","-2,181,943,840"]
+            ]
+        }
+        "#;
+        let response: Response = serde_json::from_str(response).unwrap();
+        response
+            .data
+            .into_iter()
+            .filter_map(|data| match data {
+                Data::TotalInstitutionalInvestors(d) => Some(d),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn single_rows() -> Vec<SingleStockInstitutionalInvestors> {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 三大法人買賣超日報",
+            "fields":["證券代號","證券名稱","外陸資買進股數(不含外資自營商)","外陸資賣出股數(不含外資自營商)","外陸資買賣超股數(不含外資自營商)","外資自營商買進股數","外資自營商賣出股數","外資自營商買賣超股數","投信買進股數","投信賣出股數","投信買賣超股數","自營商買賣超股數","自營商買進股數(自行買賣)","自營商賣出股數(自行買賣)","自營商買賣超股數(自行買賣)","自營商買進股數(避險)","自營商賣出股數(避險)","自營商買賣超股數(避險)","三大法人買賣超股數"],
+            "data":[
+                ["0050","元大台灣50      ","1,316,473","1,079,050","237,423","0","0","0","0","0","0","3,000","3,000","0","3,000","0","0","0","240,423"],
+                ["2330","台積電          ","20,325,158","25,909,283","-5,584,125","0","0","0","295,000","161,000","134,000","-581,233","249,000","188,000","61,000","352,767","995,000","-642,233","-6,031,358"]
+            ]
+        }
+        "#;
+        let response: Response = serde_json::from_str(response).unwrap();
+        response
+            .data
+            .into_iter()
+            .filter_map(|data| match data {
+                Data::SingleStockInstitutionalInvestors(d) => Some(d),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn count(store: &Store, table: &str) -> i64 {
+        store
+            .conn
+            .query_row(&format!("SELECT count(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_upsert() {
+        let mut store = Store::open_in_memory().unwrap();
+        let day = date("2021-02-05");
+
+        assert_eq!(
+            store
+                .upsert_total_institutional(day, DateType::Day, &total_rows())
+                .unwrap(),
+            2
+        );
+        // fetched again, and once more as a week
+        store
+            .upsert_total_institutional(day, DateType::Day, &total_rows())
+            .unwrap();
+        store
+            .upsert_total_institutional(day, DateType::Week, &total_rows())
+            .unwrap();
+        assert_eq!(count(&store, TOTAL_INSTITUTIONAL), 4);
+
+        store
+            .upsert_single_stock_institutional(day, DateType::Day, &single_rows())
+            .unwrap();
+        let (name, difference): (String, i64) = store
+            .conn
+            .query_row(
+                "SELECT stock_name, total_difference FROM single_stock_institutional WHERE date = '2021-02-05' AND date_type = 'day' AND stock_id = '2330'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(name, "台積電");
+        assert_eq!(difference, -6031358);
+    }
+
+    #[test]
+    fn test_schema_has_the_row_columns() {
+        let store = Store::open_in_memory().unwrap();
+        let test_cases = vec![
+            (
+                TOTAL_INSTITUTIONAL,
+                serde_json::to_value(&total_rows()[0]).unwrap(),
+            ),
+            (
+                SINGLE_STOCK_INSTITUTIONAL,
+                serde_json::to_value(&single_rows()[0]).unwrap(),
+            ),
+        ];
+
+        for (table, row) in test_cases {
+            let mut fields: Vec<String> = row.as_object().unwrap().keys().cloned().collect();
+            fields.sort();
+            let mut columns: Vec<String> = store
+                .conn
+                .prepare(&format!("PRAGMA table_info({})", table))
+                .unwrap()
+                .query_map([], |row| row.get(1))
+                .unwrap()
+                .map(|column| column.unwrap())
+                .filter(|column: &String| column != "date" && column != "date_type")
+                .collect();
+            columns.sort();
+            assert_eq!(fields, columns, "[{}]", table);
+        }
+    }
+
+    #[test]
+    fn test_upsert_keeps_english_names() {
+        let mut store = Store::open_in_memory().unwrap();
+        let day = date("2021-02-05");
+        let english = TotalInstitutionalInvestors::with_english_names(total_rows(), total_rows());
+        store
+            .upsert_total_institutional(day, DateType::Day, &english)
+            .unwrap();
+        store
+            .upsert_total_institutional(day, DateType::Day, &total_rows())
+            .unwrap();
+
+        let name_en: Option<String> = store
+            .conn
+            .query_row(
+                "SELECT name_en FROM total_institutional WHERE name = '合計'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(name_en.as_deref(), Some("合計"));
+    }
+
+    #[test]
+    fn test_sync() {
+        let mut store = Store::open_in_memory().unwrap();
+        let today = date("2021-02-09");
+        let requests = Cell::new(0);
+        let get_single = |d: NaiveDate| {
+            requests.set(requests.get() + 1);
+            match d.format(DATE_FORMAT).to_string().as_str() {
+                "2021-02-05" | "2021-02-08" => Ok(single_rows()),
+                _ => Err(TwseError::TWSEError(
+                    "很抱歉，沒有符合條件的資料!".to_string(),
+                )),
+            }
+        };
+        let get_total = |d: NaiveDate| {
+            requests.set(requests.get() + 1);
+            match d.format(DATE_FORMAT).to_string().as_str() {
+                "2021-02-05" | "2021-02-08" => Ok(total_rows()),
+                _ => Err(TwseError::TWSEError(
+                    "很抱歉，沒有符合條件的資料!".to_string(),
+                )),
+            }
+        };
+
+        // 02-04 is a holiday and the report of 02-09 is not out yet
+        let got = store
+            .sync_with(date("2021-02-04"), today, today, get_single, get_total)
+            .unwrap();
+        assert_eq!(
+            got,
+            SyncReport {
+                downloaded: 6,
+                skipped: 0,
+                rows: 8
+            }
+        );
+        assert_eq!(requests.get(), 8);
+        assert!(store
+            .is_downloaded(
+                Endpoint::TotalInstitutional,
+                date("2021-02-04"),
+                DateType::Day
+            )
+            .unwrap());
+        assert!(!store
+            .is_downloaded(
+                Endpoint::TotalInstitutional,
+                date("2021-02-09"),
+                DateType::Day
+            )
+            .unwrap());
+
+        requests.set(0);
+        let got = store
+            .sync_with(date("2021-02-04"), today, today, get_single, get_total)
+            .unwrap();
+        assert_eq!(got.downloaded, 0);
+        assert_eq!(got.skipped, 6);
+        assert_eq!(requests.get(), 2);
+        assert_eq!(count(&store, SINGLE_STOCK_INSTITUTIONAL), 4);
+        assert_eq!(count(&store, TOTAL_INSTITUTIONAL), 4);
+
+        // another industry is another download
+        assert!(!store
+            .is_downloaded(
+                Endpoint::SingleStockInstitutional(IndustryType::Semiconductor),
+                date("2021-02-05"),
+                DateType::Day
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn test_sync_stops_on_errors() {
+        let mut store = Store::open_in_memory().unwrap();
+        let day = date("2021-02-05");
+        let got = store.sync_with(
+            day,
+            day,
+            date("2021-02-09"),
+            |_| Err(TwseError::EnumCastingError),
+            |_| Ok(total_rows()),
+        );
+        assert!(matches!(got, Err(TwseError::EnumCastingError)));
+
+        // only the stat of a day without data is a holiday
+        let got = store.sync_with(
+            day,
+            day,
+            date("2021-02-09"),
+            |_| {
+                Err(TwseError::TWSEError(
+                    "查詢日期大於今日，請重新查詢!".to_string(),
+                ))
+            },
+            |_| Ok(total_rows()),
+        );
+        assert!(matches!(got, Err(TwseError::TWSEError(_))));
+        assert!(!store
+            .is_downloaded(
+                Endpoint::SingleStockInstitutional(IndustryType::All),
+                day,
+                DateType::Day
+            )
+            .unwrap());
+    }
+}
@@ -0,0 +1,452 @@
+use crate::args::TaifexOpenInterestArgs;
+use crate::encoding;
+use crate::errors::{TwseError, TAIFEX_NO_DATA};
+use crate::schema::{clean_string, parse_i64, parse_u64};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+
+const FUTURES_OPEN_INTEREST_LINK: &str = "https://www.taifex.com.tw/cht/3/futContractsDateDown";
+const OPTIONS_OPEN_INTEREST_LINK: &str = "https://www.taifex.com.tw/cht/3/callsAndPutsDateDown";
+const TAIFEX_DATE_FORMAT: &str = "%Y/%m/%d";
+// every TAIFEX download starts with the 日期 column, otherwise an html page is served
+const TAIFEX_HEADER_PREFIX: &str = "日期";
+
+// 期貨契約 三大法人交易及未平倉口數與契約金額
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct FuturesOpenInterest {
+    #[serde(deserialize_with = "parse_taifex_date")]
+    date: NaiveDate,
+    #[serde(deserialize_with = "clean_string")]
+    contract: String,
+    #[serde(deserialize_with = "parse_investor_category")]
+    investor: InvestorCategory,
+    #[serde(deserialize_with = "parse_u64")]
+    long_trade_volume: u64,
+    // thousand NTD
+    #[serde(deserialize_with = "parse_u64")]
+    long_trade_value: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    short_trade_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    short_trade_value: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    net_trade_volume: i64,
+    #[serde(deserialize_with = "parse_i64")]
+    net_trade_value: i64,
+    #[serde(deserialize_with = "parse_u64")]
+    long_open_interest_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    long_open_interest_value: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    short_open_interest_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    short_open_interest_value: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    net_open_interest_volume: i64,
+    #[serde(deserialize_with = "parse_i64")]
+    net_open_interest_value: i64,
+}
+
+impl FuturesOpenInterest {
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn contract(&self) -> &str {
+        &self.contract
+    }
+
+    pub fn investor(&self) -> InvestorCategory {
+        self.investor
+    }
+
+    pub fn long_trade_volume(&self) -> u64 {
+        self.long_trade_volume
+    }
+
+    pub fn long_trade_value(&self) -> u64 {
+        self.long_trade_value
+    }
+
+    pub fn short_trade_volume(&self) -> u64 {
+        self.short_trade_volume
+    }
+
+    pub fn short_trade_value(&self) -> u64 {
+        self.short_trade_value
+    }
+
+    pub fn net_trade_volume(&self) -> i64 {
+        self.net_trade_volume
+    }
+
+    pub fn net_trade_value(&self) -> i64 {
+        self.net_trade_value
+    }
+
+    pub fn long_open_interest_volume(&self) -> u64 {
+        self.long_open_interest_volume
+    }
+
+    pub fn long_open_interest_value(&self) -> u64 {
+        self.long_open_interest_value
+    }
+
+    pub fn short_open_interest_volume(&self) -> u64 {
+        self.short_open_interest_volume
+    }
+
+    pub fn short_open_interest_value(&self) -> u64 {
+        self.short_open_interest_value
+    }
+
+    pub fn net_open_interest_volume(&self) -> i64 {
+        self.net_open_interest_volume
+    }
+
+    pub fn net_open_interest_value(&self) -> i64 {
+        self.net_open_interest_value
+    }
+}
+
+// 選擇權買賣權 三大法人交易及未平倉口數與契約金額
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct OptionsOpenInterest {
+    #[serde(deserialize_with = "parse_taifex_date")]
+    date: NaiveDate,
+    #[serde(deserialize_with = "clean_string")]
+    contract: String,
+    #[serde(deserialize_with = "parse_option_type")]
+    option_type: OptionType,
+    #[serde(deserialize_with = "parse_investor_category")]
+    investor: InvestorCategory,
+    #[serde(deserialize_with = "parse_u64")]
+    buy_trade_volume: u64,
+    // thousand NTD
+    #[serde(deserialize_with = "parse_u64")]
+    buy_trade_value: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sell_trade_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sell_trade_value: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    net_trade_volume: i64,
+    #[serde(deserialize_with = "parse_i64")]
+    net_trade_value: i64,
+    #[serde(deserialize_with = "parse_u64")]
+    buy_open_interest_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    buy_open_interest_value: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sell_open_interest_volume: u64,
+    #[serde(deserialize_with = "parse_u64")]
+    sell_open_interest_value: u64,
+    #[serde(deserialize_with = "parse_i64")]
+    net_open_interest_volume: i64,
+    #[serde(deserialize_with = "parse_i64")]
+    net_open_interest_value: i64,
+}
+
+impl OptionsOpenInterest {
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn contract(&self) -> &str {
+        &self.contract
+    }
+
+    pub fn option_type(&self) -> OptionType {
+        self.option_type
+    }
+
+    pub fn investor(&self) -> InvestorCategory {
+        self.investor
+    }
+
+    pub fn buy_trade_volume(&self) -> u64 {
+        self.buy_trade_volume
+    }
+
+    pub fn buy_trade_value(&self) -> u64 {
+        self.buy_trade_value
+    }
+
+    pub fn sell_trade_volume(&self) -> u64 {
+        self.sell_trade_volume
+    }
+
+    pub fn sell_trade_value(&self) -> u64 {
+        self.sell_trade_value
+    }
+
+    pub fn net_trade_volume(&self) -> i64 {
+        self.net_trade_volume
+    }
+
+    pub fn net_trade_value(&self) -> i64 {
+        self.net_trade_value
+    }
+
+    pub fn buy_open_interest_volume(&self) -> u64 {
+        self.buy_open_interest_volume
+    }
+
+    pub fn buy_open_interest_value(&self) -> u64 {
+        self.buy_open_interest_value
+    }
+
+    pub fn sell_open_interest_volume(&self) -> u64 {
+        self.sell_open_interest_volume
+    }
+
+    pub fn sell_open_interest_value(&self) -> u64 {
+        self.sell_open_interest_value
+    }
+
+    pub fn net_open_interest_volume(&self) -> i64 {
+        self.net_open_interest_volume
+    }
+
+    pub fn net_open_interest_value(&self) -> i64 {
+        self.net_open_interest_value
+    }
+}
+
+// 身份別
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum InvestorCategory {
+    Dealer,
+    InvestmentTrust,
+    ForeignInvestor,
+}
+
+// 買賣權別
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+fn get_taifex_csv(link: &str, args: TaifexOpenInterestArgs) -> Result<String, TwseError> {
+    let start_date = format!("{}", args.start_date.format(TAIFEX_DATE_FORMAT));
+    let end_date = format!("{}", args.end_date.format(TAIFEX_DATE_FORMAT));
+
+    let response = ureq::post(link)
+        .send_form(&[("queryStartDate", &start_date), ("queryEndDate", &end_date)])?;
+
+    encoding::read_response(response)
+}
+
+fn parse_taifex_csv<T: DeserializeOwned>(csv: &str) -> Result<Vec<T>, TwseError> {
+    let csv = csv.trim_start_matches('\u{feff}').trim_start();
+    if !csv.starts_with(TAIFEX_HEADER_PREFIX) {
+        return Err(TwseError::TWSEError(TAIFEX_NO_DATA.to_string()));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(csv.as_bytes());
+
+    let mut ret: Vec<T> = vec![];
+    for record in reader.records() {
+        let mut record = record?;
+        // cells can be padded with spaces
+        record.trim();
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        ret.push(record.deserialize(None)?);
+    }
+
+    if ret.is_empty() {
+        return Err(TwseError::TWSEError(TAIFEX_NO_DATA.to_string()));
+    }
+
+    Ok(ret)
+}
+
+pub fn get_futures_open_interest(
+    args: TaifexOpenInterestArgs,
+) -> Result<Vec<FuturesOpenInterest>, TwseError> {
+    let csv = get_taifex_csv(FUTURES_OPEN_INTEREST_LINK, args)?;
+    parse_taifex_csv(&csv)
+}
+
+pub fn get_options_open_interest(
+    args: TaifexOpenInterestArgs,
+) -> Result<Vec<OptionsOpenInterest>, TwseError> {
+    let csv = get_taifex_csv(OPTIONS_OPEN_INTEREST_LINK, args)?;
+    parse_taifex_csv(&csv)
+}
+
+fn parse_taifex_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    NaiveDate::parse_from_str(s.trim(), TAIFEX_DATE_FORMAT).map_err(serde::de::Error::custom)
+}
+
+fn parse_investor_category<'de, D>(deserializer: D) -> Result<InvestorCategory, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.trim() {
+        "自營商" => Ok(InvestorCategory::Dealer),
+        "投信" => Ok(InvestorCategory::InvestmentTrust),
+        // 外資 before the 2017 rename
+        "外資及陸資" | "外資" => Ok(InvestorCategory::ForeignInvestor),
+        _ => Err(serde::de::Error::custom(format!(
+            "unknown investor category {}",
+            s
+        ))),
+    }
+}
+
+fn parse_option_type<'de, D>(deserializer: D) -> Result<OptionType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.trim() {
+        "買權" | "CALL" => Ok(OptionType::Call),
+        "賣權" | "PUT" => Ok(OptionType::Put),
+        _ => Err(serde::de::Error::custom(format!(
+            "unknown option type {}",
+            s
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_parse_futures_open_interest() -> Result<()> {
+        let csv = "日期,商品名稱,身份別,多方交易口數,多方交易契約金額(千元),空方交易口數,空方交易契約金額(千元),多空交易口數淨額,多空交易契約金額淨額(千元),多方未平倉口數,多方未平倉契約金額(千元),空方未平倉口數,空方未平倉契約金額(千元),多空未平倉口數淨額,多空未平倉契約金額淨額(千元)\r\n\
+2021/02/05,臺股期貨,自營商,10325,3267105,10148,3211196,177,55909,11245,3558851,6620,2095018,4625,1463833\r\n\
+2021/02/05,臺股期貨,投信,320,101279,442,139896,-122,-38617,1236,391136,21768,6888879,-20532,-6497743\r\n\
+2021/02/05,臺股期貨,外資及陸資,68930,21808434,65722,20793718,3208,1014716,48672,15402659,42069,13313120,6603,2089539\r\n";
+        let rows: Vec<FuturesOpenInterest> = parse_taifex_csv(csv)?;
+        assert_eq!(rows.len(), 3);
+
+        let wants = [
+            (InvestorCategory::Dealer, 10325, 177, 4625, 1463833),
+            (
+                InvestorCategory::InvestmentTrust,
+                320,
+                -122,
+                -20532,
+                -6497743,
+            ),
+            (
+                InvestorCategory::ForeignInvestor,
+                68930,
+                3208,
+                6603,
+                2089539,
+            ),
+        ];
+        for (n, row) in rows.iter().enumerate() {
+            assert_eq!(row.date, NaiveDate::from_ymd_opt(2021, 2, 5).unwrap());
+            assert_eq!(row.contract, "臺股期貨");
+            assert_eq!(row.investor, wants[n].0);
+            assert_eq!(row.long_trade_volume, wants[n].1);
+            assert_eq!(row.net_trade_volume, wants[n].2);
+            assert_eq!(row.net_open_interest_volume, wants[n].3);
+            assert_eq!(row.net_open_interest_value, wants[n].4);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_options_open_interest() -> Result<()> {
+        let csv = "日期,商品名稱,買賣權別,身份別,買方交易口數,買方交易契約金額(千元),賣方交易口數,賣方交易契約金額(千元),交易口數買賣淨額,交易契約金額買賣淨額(千元),買方未平倉口數,買方未平倉契約金額(千元),賣方未平倉口數,賣方未平倉契約金額(千元),未平倉口數買賣淨額,未平倉契約金額買賣淨額(千元),\r\n\
+2021/02/05,臺指選擇權,買權,自營商,130224,344419,123741,330522,6483,13897,58237,253286,35484,215542,22753,37744,\r\n\
+2021/02/05,臺指選擇權,賣權,外資及陸資,52340,90543,55210,99875,-2870,-9332,38801,47190,21033,31457,17768,15733,\r\n";
+        let rows: Vec<OptionsOpenInterest> = parse_taifex_csv(csv)?;
+        assert_eq!(rows.len(), 2);
+
+        assert_eq!(rows[0].option_type(), OptionType::Call);
+        assert_eq!(rows[0].investor(), InvestorCategory::Dealer);
+        assert_eq!(rows[0].buy_trade_volume(), 130224);
+        assert_eq!(rows[0].net_open_interest_volume(), 22753);
+        assert_eq!(rows[1].option_type(), OptionType::Put);
+        assert_eq!(rows[1].investor(), InvestorCategory::ForeignInvestor);
+        assert_eq!(rows[1].net_trade_value(), -9332);
+        assert_eq!(rows[1].net_open_interest_value(), 15733);
+
+        let row = serde_json::to_value(&rows[1])?;
+        assert_eq!(row["date"], "2021-02-05");
+        assert_eq!(row["option_type"], "Put");
+        assert_eq!(row["investor"], "ForeignInvestor");
+        assert_eq!(row["net_trade_value"], -9332);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_taifex_csv_without_data() {
+        let html = "<html><body><p>查無資料</p></body></html>";
+        let got = parse_taifex_csv::<FuturesOpenInterest>(html);
+        assert!(matches!(got, Err(TwseError::TWSEError(_))));
+        assert!(got.unwrap_err().is_no_data());
+
+        let header_only = "日期,商品名稱,身份別\r\n";
+        let got = parse_taifex_csv::<FuturesOpenInterest>(header_only);
+        assert!(matches!(got, Err(TwseError::TWSEError(_))));
+    }
+
+    #[test]
+    fn test_get_open_interest() {
+        let date = NaiveDate::parse_from_str("2021-02-05", "%Y-%m-%d").unwrap();
+        let wrong_date = NaiveDate::parse_from_str("6021-02-05", "%Y-%m-%d").unwrap();
+
+        let test_cases = vec![
+            (
+                TaifexOpenInterestArgs {
+                    start_date: date,
+                    end_date: date,
+                },
+                "date should be ok",
+                true,
+            ),
+            (
+                TaifexOpenInterestArgs {
+                    start_date: wrong_date,
+                    end_date: wrong_date,
+                },
+                "date should not be ok",
+                false,
+            ),
+        ];
+
+        for (input, description, want) in test_cases {
+            let got = get_futures_open_interest(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[futures {}]: input:{:?}",
+                description,
+                input
+            );
+            let got = get_options_open_interest(input);
+            assert_eq!(
+                want,
+                got.is_ok(),
+                "[options {}]: input:{:?}",
+                description,
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,468 @@
+use crate::errors::TwseError;
+use crate::schema::{Header, Response};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::io::{self, Write};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+// the column of the report date, the same as in the parquet files
+const REPORT_DATE: &str = "report_date";
+
+// the header row of the csv, the report date is always the first column,
+// named report_date as some rows have a date of their own
+#[derive(Debug, Clone)]
+pub enum Headers {
+    // the field names of the row type, e.g. stock_id
+    FieldNames,
+    // the fields of a response, e.g. 證券代號, columns after them keep their field names
+    Fields { date: String, fields: Vec<String> },
+}
+
+impl Headers {
+    // e.g. 證券代號 of SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS
+    pub fn chinese(headers: &[Header]) -> Headers {
+        Headers::Fields {
+            date: "資料日期".to_string(),
+            fields: headers.iter().map(|h| h.zh.to_string()).collect(),
+        }
+    }
+
+    // e.g. Security Code of SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS
+    pub fn english(headers: &[Header]) -> Headers {
+        Headers::Fields {
+            date: "Report Date".to_string(),
+            fields: headers.iter().map(|h| h.en.to_string()).collect(),
+        }
+    }
+
+    // the fields as the response has them, lang=en responses only have ascii fields
+    pub fn from_response(response: &Response) -> Headers {
+        let english = response.fields.iter().all(|field| field.is_ascii());
+        Headers::Fields {
+            date: if english {
+                "Report Date"
+            } else {
+                "資料日期"
+            }
+            .to_string(),
+            fields: response
+                .fields
+                .iter()
+                .map(|f| f.trim().to_string())
+                .collect(),
+        }
+    }
+}
+
+// streams the rows of many reports into a single csv
+pub struct CsvWriter<W: Write> {
+    writer: csv::Writer<W>,
+    headers: Headers,
+    header_written: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(out: W, headers: Headers) -> CsvWriter<W> {
+        CsvWriter {
+            writer: csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(out),
+            headers,
+            header_written: false,
+        }
+    }
+
+    pub fn write<T: Serialize>(&mut self, date: NaiveDate, rows: &[T]) -> Result<(), TwseError> {
+        let date = date.format(DATE_FORMAT).to_string();
+        for row in rows.iter() {
+            if !self.header_written {
+                self.write_header(row)?;
+                self.header_written = true;
+            }
+            self.writer.serialize((&date, row))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), TwseError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn write_header<T: Serialize>(&mut self, row: &T) -> Result<(), TwseError> {
+        let names = field_names(row)?;
+        let header = match &self.headers {
+            Headers::FieldNames => {
+                let mut header = vec![REPORT_DATE.to_string()];
+                header.extend(names);
+                header
+            }
+            Headers::Fields { date, fields } => {
+                if fields.len() > names.len() {
+                    return Err(TwseError::FieldsError(fields.join(",")));
+                }
+                let mut header = vec![date.clone()];
+                header.extend(fields.iter().cloned());
+                header.extend(names.into_iter().skip(fields.len()));
+                header
+            }
+        };
+
+        self.writer.write_record(&header)?;
+        Ok(())
+    }
+}
+
+// csv takes the names of a struct from its first serialized row
+pub(crate) fn field_names<T: Serialize>(row: &T) -> Result<Vec<String>, TwseError> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.serialize(row)?;
+    let body = writer
+        .into_inner()
+        .map_err(|e| TwseError::CsvError(e.into_error().into()))?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(body.as_slice());
+    match reader.records().next() {
+        Some(record) => Ok(record?.iter().map(|name| name.to_string()).collect()),
+        None => Ok(vec![]),
+    }
+}
+
+#[derive(Serialize)]
+struct DatedRow<'a, T> {
+    report_date: NaiveDate,
+    #[serde(flatten)]
+    row: &'a T,
+}
+
+// streams the rows as json objects, one per line
+pub struct NdjsonWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(out: W) -> NdjsonWriter<W> {
+        NdjsonWriter { out }
+    }
+
+    pub fn write<T: Serialize>(&mut self, date: NaiveDate, rows: &[T]) -> Result<(), TwseError> {
+        for row in rows.iter() {
+            serde_json::to_writer(
+                &mut self.out,
+                &DatedRow {
+                    report_date: date,
+                    row,
+                },
+            )
+            .map_err(io::Error::from)?;
+            self.out.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), TwseError> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::realtime::Quote;
+    use crate::schema::{
+        DailyMarketSnapshot, Data, DispositionStock, MarketDailySummary, ReportResponse, Response,
+        SingleStockInstitutionalInvestors, TaiexHistory, TotalInstitutionalInvestors,
+        SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS, TOTAL_INSTITUTIONAL_INVESTORS_HEADERS,
+    };
+    use rust_decimal_macros::dec;
+    use serde::de::DeserializeOwned;
+    use std::collections::HashMap;
+
+    fn total_response() -> Response {
+        let response = r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月05日 三大法人買賣金額統計表",
+            "fields":["單位名稱","買進金額","賣出金額","買賣差額"],
+            "data":[
+                ["自營商(自行買賣)","3,720,692,397","2,657,469,622","1,063,222,775"],
+                ["合計","87,532,849,369","89,714,793,209","-2,181,943,840"]
+            ]
+        }
+        "#;
+        serde_json::from_str(response).unwrap()
+    }
+
+    fn total_rows() -> Vec<TotalInstitutionalInvestors> {
+        total_response()
+            .data
+            .into_iter()
+            .filter_map(|data| match data {
+                Data::TotalInstitutionalInvestors(d) => Some(d),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, 2, 5).unwrap()
+    }
+
+    #[test]
+    fn test_csv_writer() {
+        let test_cases = [
+            (
+                Headers::FieldNames,
+                "report_date,name,buy,sell,difference,name_en",
+                "field names",
+            ),
+            (
+                Headers::chinese(&TOTAL_INSTITUTIONAL_INVESTORS_HEADERS),
+                "資料日期,單位名稱,買進金額,賣出金額,買賣差額,name_en",
+                "chinese",
+            ),
+            (
+                Headers::english(&TOTAL_INSTITUTIONAL_INVESTORS_HEADERS),
+                "Report Date,Item,Total Buy,Total Sell,Difference,name_en",
+                "english",
+            ),
+            (
+                Headers::from_response(&total_response()),
+                "資料日期,單位名稱,買進金額,賣出金額,買賣差額,name_en",
+                "response",
+            ),
+        ];
+
+        for (headers, want, description) in test_cases.iter() {
+            let mut out = vec![];
+            let mut writer = CsvWriter::new(&mut out, headers.clone());
+            writer.write(date(), &total_rows()).unwrap();
+            writer
+                .write(date().succ_opt().unwrap(), &total_rows()[1..])
+                .unwrap();
+            writer.flush().unwrap();
+            drop(writer);
+
+            let got = String::from_utf8(out).unwrap();
+            let lines: Vec<&str> = got.lines().collect();
+            assert_eq!(lines.len(), 4, "[{}]", description);
+            assert_eq!(lines[0], *want, "[{}]", description);
+            assert_eq!(
+                lines[1], "2021-02-05,自營商(自行買賣),3720692397,2657469622,1063222775,",
+                "[{}]",
+                description
+            );
+            assert_eq!(
+                lines[3], "2021-02-06,合計,87532849369,89714793209,-2181943840,",
+                "[{}]",
+                description
+            );
+        }
+    }
+
+    #[test]
+    fn test_headers_from_response() {
+        let mut response = total_response();
+        response.fields = TOTAL_INSTITUTIONAL_INVESTORS_HEADERS
+            .iter()
+            .map(|h| h.en.to_string())
+            .collect();
+
+        match Headers::from_response(&response) {
+            Headers::Fields { date, fields } => {
+                assert_eq!(date, "Report Date");
+                assert_eq!(
+                    fields,
+                    vec!["Item", "Total Buy", "Total Sell", "Difference"]
+                );
+            }
+            headers => assert!(false, "unexpected {:?}", headers),
+        }
+    }
+
+    #[test]
+    fn test_csv_writer_with_wrong_fields() {
+        let mut writer = CsvWriter::new(
+            vec![],
+            Headers::chinese(&SINGLE_STOCK_INSTITUTIONAL_INVESTORS_HEADERS),
+        );
+        let got = writer.write(date(), &total_rows());
+        assert!(matches!(got, Err(TwseError::FieldsError(_))));
+
+        // nothing is written without rows
+        let mut out = vec![];
+        let mut writer = CsvWriter::new(&mut out, Headers::FieldNames);
+        writer
+            .write::<SingleStockInstitutionalInvestors>(date(), &[])
+            .unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_ndjson_writer() {
+        let mut out = vec![];
+        let mut writer = NdjsonWriter::new(&mut out);
+        writer.write(date(), &total_rows()).unwrap();
+        writer.flush().unwrap();
+
+        let got = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = got.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1],
+            r#"{"report_date":"2021-02-05","name":"合計","buy":87532849369,"sell":89714793209,"difference":-2181943840,"name_en":null}"#
+        );
+    }
+
+    fn data<T: DeserializeOwned>(response: &str) -> Vec<T> {
+        let response: ReportResponse<T> = serde_json::from_str(response).unwrap();
+        response.data
+    }
+
+    // writes the rows with their field names and reads the csv back
+    fn csv_round_trip<T: Serialize>(rows: &[T]) -> Vec<HashMap<String, String>> {
+        let mut out = vec![];
+        let mut writer = CsvWriter::new(&mut out, Headers::FieldNames);
+        writer.write(date(), rows).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        let mut reader = csv::Reader::from_reader(out.as_slice());
+        let headers = reader.headers().unwrap().clone();
+        let mut names: Vec<&str> = headers.iter().collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(
+            names.len(),
+            headers.len(),
+            "duplicated columns {:?}",
+            headers
+        );
+        reader.deserialize().map(|record| record.unwrap()).collect()
+    }
+
+    #[test]
+    fn test_rows_with_their_own_date() {
+        let rows: Vec<TaiexHistory> = data(
+            r#"
+        {
+            "stat":"OK",
+            "date":"20210201",
+            "title":"110年02月 發行量加權股價指數歷史資料",
+            "fields":["日期","開盤指數","最高指數","最低指數","收盤指數"],
+            "data":[["110/02/01","15,410.09","15,651.58","15,291.51","15,410.09"]]
+        }
+        "#,
+        );
+
+        let records = csv_round_trip(&rows);
+        assert_eq!(records[0]["report_date"], "2021-02-05");
+        assert_eq!(records[0]["date"], "2021-02-01");
+
+        let mut out = vec![];
+        let mut writer = NdjsonWriter::new(&mut out);
+        writer.write(date(), &rows).unwrap();
+        let got = String::from_utf8(out).unwrap();
+        assert_eq!(got.matches(r#""date":"#).count(), 1);
+        let json: serde_json::Value = serde_json::from_str(got.trim()).unwrap();
+        assert_eq!(json["report_date"], "2021-02-05");
+        assert_eq!(json["date"], "2021-02-01");
+    }
+
+    #[test]
+    fn test_csv_disposition_stocks() {
+        let rows: Vec<DispositionStock> = data(
+            r#"
+        {
+            "stat":"OK",
+            "date":"20210205",
+            "title":"110年02月01日至110年02月05日 公布處置股票資訊",
+            "fields":["編號","公布日期","證券代號","證券名稱","累計","處置條件","處置起迄時間","處置措施","處置內容","備註"],
+            "data":[
+                ["2","110/02/05","3051","力特","2","第一款<br>第二款","110/02/08～110/02/22","第二次處置","以人工管制之撮合終端機執行撮合作業(約每二十分鐘撮合一次)",""]
+            ]
+        }
+        "#,
+        );
+        assert_eq!(rows.len(), 1);
+
+        let records = csv_round_trip(&rows);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["stock_id"], "3051");
+        assert_eq!(records[0]["reasons"], "第一款\n第二款");
+        assert_eq!(records[0]["period_start"], "2021-02-08");
+        assert_eq!(records[0]["period_end"], "2021-02-22");
+        assert_eq!(records[0]["measure"], "Second");
+    }
+
+    #[test]
+    fn test_csv_daily_market_snapshots() {
+        let summaries: Vec<MarketDailySummary> = data(
+            r#"
+        {
+            "stat":"OK",
+            "date":"20210201",
+            "title":"110年02月市場成交資訊",
+            "fields":["日期","成交股數","成交金額","成交筆數","發行量加權股價指數","漲跌點數"],
+            "data":[
+                ["110/02/04","6,410,870,521","298,418,337,446","1,858,111","15,806.64","46.59"],
+                ["110/02/05","5,645,915,064","286,296,431,474","1,690,022","15,802.37","-4.27"]
+            ]
+        }
+        "#,
+        );
+        let snapshots = DailyMarketSnapshot::join(summaries, vec![(date(), total_rows())]);
+
+        let records = csv_round_trip(&snapshots);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["date"], "2021-02-04");
+        assert_eq!(records[0]["institutional_investors_1_name"], "");
+        assert_eq!(records[1]["summary_taiex"], "15802.37");
+        assert_eq!(
+            records[1]["institutional_investors_1_name"],
+            "自營商(自行買賣)"
+        );
+        assert_eq!(
+            records[1]["institutional_investors_2_difference"],
+            "-2181943840"
+        );
+        assert_eq!(records[1]["institutional_investors_6_buy"], "");
+    }
+
+    #[test]
+    fn test_csv_quotes() {
+        let quote = Quote {
+            stock_id: "2330".to_string(),
+            stock_name: "台積電".to_string(),
+            datetime: date().and_hms_opt(9, 30, 1).unwrap(),
+            last_price: Some(dec!(585)),
+            last_volume: Some(3),
+            accumulated_volume: 14589,
+            open: None,
+            high: None,
+            low: None,
+            previous_close: dec!(593),
+            limit_up: dec!(652),
+            limit_down: dec!(534),
+            best_bids: vec![(dec!(585), 55), (dec!(584), 384)],
+            best_asks: vec![],
+        };
+
+        let records = csv_round_trip(&[quote]);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["last_price"], "585");
+        assert_eq!(records[0]["open"], "");
+        assert_eq!(records[0]["bid_1_price"], "585");
+        assert_eq!(records[0]["bid_2_volume"], "384");
+        assert_eq!(records[0]["bid_3_price"], "");
+        assert_eq!(records[0]["ask_5_volume"], "");
+    }
+}